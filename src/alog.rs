@@ -0,0 +1,42 @@
+//! Logging under a tag given at the call site.
+
+use super::*;
+
+/// Logs a message through the global android logger, at the given level and
+/// under the given tag, formatted like [`format!`].
+///
+/// The tag replaces the one the configuration would pick (e.g. the module
+/// path, or the one of [`Config::with_tag()`]), without overloading the
+/// target of `log::info!(target: ..)`: it's also the target of the record,
+/// as for [`log_if()`]. The message is only formatted if `level` is enabled.
+///
+/// This bypasses the `log` facade, and so its compile-time level features
+/// (e.g. `max_level_info`), but not the filters of the android logger itself
+/// (e.g. [`Config::with_max_level()`]).
+///
+/// ```
+/// use log::Level;
+///
+/// let attempts = 3;
+/// android_logger::alog!(Level::Warn, "my_app", "retried {attempts} times");
+/// ```
+#[macro_export]
+macro_rules! alog {
+    ($level:expr, $tag:expr, $($arg:tt)+) => {
+        $crate::__alog($level, $tag, ::std::format_args!($($arg)+))
+    };
+}
+
+/// Implementation of [`alog!`].
+#[doc(hidden)]
+pub fn __alog(level: Level, tag: &str, args: fmt::Arguments) {
+    ANDROID_LOGGER.get_or_init(AndroidLogger::default).emit(
+        &Record::builder()
+            .level(level)
+            .target(tag)
+            .args(args)
+            .build(),
+        Some(tag),
+        None,
+    );
+}
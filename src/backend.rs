@@ -0,0 +1,139 @@
+//! Custom destinations of the log output.
+
+use super::*;
+
+/// Destination of the log messages, replacing the Android logging system,
+/// see [`Config::with_backend()`].
+///
+/// Messages are handed over once formatted and split, exactly as they would
+/// be written to logcat.
+pub trait LogBackend: Sync + Send {
+    /// Writes a single log message to the `buf_id` buffer (`None` for the
+    /// default one), with the priority of `level`.
+    fn write(&self, buf_id: Option<LogId>, level: Level, tag: &CStr, msg: &CStr);
+}
+
+/// Writes a log message to `backend`, unless captured by [`test_capture()`]
+/// or denied by the `deny-logging` feature, like [`android_log()`] does.
+pub(crate) fn write(
+    backend: &dyn LogBackend,
+    buf_id: Option<LogId>,
+    level: Level,
+    tag: &CStr,
+    msg: &CStr,
+) {
+    if logging_denied() {
+        return;
+    }
+    if capture_record(buf_id, level, tag, msg) {
+        return;
+    }
+    backend.write(buf_id, level, tag, msg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+    use std::sync::Arc;
+
+    /// Collects everything written.
+    #[derive(Default)]
+    struct Collector(Mutex<Vec<CapturedRecord>>);
+
+    impl LogBackend for Collector {
+        fn write(&self, buf_id: Option<LogId>, level: Level, tag: &CStr, msg: &CStr) {
+            self.0.lock().unwrap().push(CapturedRecord {
+                buf_id,
+                level,
+                tag: tag.to_str().unwrap().to_owned(),
+                msg: msg.to_str().unwrap().to_owned(),
+            });
+        }
+    }
+
+    #[test]
+    fn custom_backend_gets_all_writes() {
+        let collector = Arc::new(Collector::default());
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("app")
+                .tee_to_buffer(LogId::Crash)
+                .with_backend(collector.clone()),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Error)
+                .args(format_args!("{}", "a".repeat(LOGGING_MSG_MAX_LEN + 1)))
+                .build(),
+        );
+
+        assert!(take_emitted().is_empty());
+        let written = collector.0.lock().unwrap();
+        let written: Vec<_> = written
+            .iter()
+            .map(|r| (r.buf_id, r.level, r.tag.as_str(), r.msg.len()))
+            .collect();
+        assert_eq!(
+            written,
+            [
+                (None, Level::Error, "app", LOGGING_MSG_MAX_LEN),
+                (Some(LogId::Crash), Level::Error, "app", LOGGING_MSG_MAX_LEN),
+                (None, Level::Error, "app", 3),
+                (Some(LogId::Crash), Level::Error, "app", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_backend_is_captured() {
+        let collector = Arc::new(Collector::default());
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_backend(collector.clone()),
+        );
+
+        let captured = test_capture(|| {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("captured"))
+                    .build(),
+            )
+        });
+
+        assert_eq!(captured.len(), 1);
+        assert!(collector.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn custom_backend_gets_other_messages() {
+        let collector = Arc::new(Collector::default());
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("app")
+                .emit_tooling_header(true)
+                .with_backend(collector.clone()),
+        );
+
+        logger.log_tooling_header();
+        logger.hexdump(Level::Info, "app", b"*");
+        logger.log_panic(&"boom");
+
+        assert!(take_emitted().is_empty());
+        let written = collector.0.lock().unwrap();
+        let msgs: Vec<_> = written.iter().map(|r| r.msg.as_str()).collect();
+        assert_eq!(
+            msgs,
+            [
+                "#android_logger format=plain tag=app",
+                "00000000  2a                                                |*|",
+                "boom",
+            ]
+        );
+    }
+}
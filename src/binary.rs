@@ -0,0 +1,120 @@
+//! Encoding of messages which aren't text.
+
+use super::*;
+
+/// How messages holding binary data are logged, see
+/// [`Config::binary_encoding()`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BinaryEncoding {
+    /// Messages are logged as is, so the Android logging system drops
+    /// everything after a nul byte.
+    #[default]
+    None,
+
+    /// Binary messages are encoded in standard base64, with padding.
+    Base64,
+
+    /// Binary messages are encoded in lowercase hex, two digits per byte.
+    Hex,
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Whether `msg` holds bytes which can't be logged as text: nul bytes, and
+/// control characters other than whitespace.
+fn is_binary(msg: &str) -> bool {
+    msg.bytes()
+        .any(|b| b.is_ascii_control() && !matches!(b, b'\n' | b'\r' | b'\t'))
+}
+
+/// Writes `args`, encoded with `encoding` if they format to a binary
+/// message.
+pub(crate) fn write_message(
+    f: &mut dyn fmt::Write,
+    args: fmt::Arguments<'_>,
+    encoding: BinaryEncoding,
+) -> fmt::Result {
+    if encoding == BinaryEncoding::None {
+        return f.write_fmt(args);
+    }
+
+    let mut msg = String::new();
+    let formatted = fmt::write(&mut msg, args);
+    if !is_binary(&msg) {
+        return f.write_str(&msg).and(formatted);
+    }
+
+    match encoding {
+        BinaryEncoding::None => unreachable!(),
+        BinaryEncoding::Base64 => write_base64(f, msg.as_bytes()),
+        BinaryEncoding::Hex => msg.bytes().try_for_each(|b| write!(f, "{b:02x}")),
+    }
+    .and(formatted)
+}
+
+fn write_base64(f: &mut dyn fmt::Write, bytes: &[u8]) -> fmt::Result {
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (n >> (18 - 6 * i)) & 0x3f;
+                f.write_char(char::from(BASE64_ALPHABET[sextet as usize]))?;
+            } else {
+                f.write_char('=')?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn encode(encoding: BinaryEncoding, msg: &str) -> String {
+        let mut s = String::new();
+        write_message(&mut s, format_args!("{msg}"), encoding).unwrap();
+        s
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(encode(BinaryEncoding::Base64, "\0"), "AA==");
+        assert_eq!(encode(BinaryEncoding::Base64, "\0a"), "AGE=");
+        assert_eq!(encode(BinaryEncoding::Base64, "\0ab"), "AGFi");
+        assert_eq!(encode(BinaryEncoding::Base64, "\x01abc"), "AWFiYw==");
+    }
+
+    #[test]
+    fn text_is_not_encoded() {
+        assert_eq!(encode(BinaryEncoding::Base64, "a\tb\nc"), "a\tb\nc");
+        assert_eq!(encode(BinaryEncoding::Hex, "abc"), "abc");
+        assert_eq!(encode(BinaryEncoding::Hex, "a\0b"), "610062");
+        assert_eq!(encode(BinaryEncoding::None, "a\0b"), "a\0b");
+    }
+
+    #[test]
+    fn binary_message_is_encoded() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .binary_encoding(BinaryEncoding::Base64),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{}", "a\0b"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].msg, "YQBi");
+    }
+}
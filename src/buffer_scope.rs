@@ -0,0 +1,99 @@
+//! Scoped override of the buffer of the current thread.
+
+use super::*;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+thread_local! {
+    /// Buffer of the innermost active [`BufferScope`] of the current thread.
+    static SCOPED_BUFFER: Cell<Option<LogId>> = const { Cell::new(None) };
+}
+
+/// Logs the records of the current thread to `buf_id`, until the returned
+/// guard is dropped.
+///
+/// This directs a block of logs (e.g. the ones of crash handling) to another
+/// buffer without reconfiguring the logger. The scoped buffer takes
+/// precedence over the configured ones ([`Config::with_log_buffer()`] and
+/// [`Config::route_module_to_buffer()`]), but not over the `android_buffer`
+/// key-value of a record (with the `kv` feature). Scopes may be nested:
+/// dropping a guard restores the buffer of the enclosing scope.
+///
+/// Most buffers are only writable by privileged processes, and writes to the
+/// others are silently dropped, see [`accessible_buffers()`].
+///
+/// ```
+/// use android_logger::LogId;
+///
+/// {
+///     let _crash = android_logger::buffer_scope(LogId::Crash);
+///     // log::error!("..") is logged to the crash buffer
+/// }
+/// // and back to the configured one here
+/// ```
+pub fn buffer_scope(buf_id: LogId) -> BufferScope {
+    BufferScope {
+        previous: SCOPED_BUFFER.replace(Some(buf_id)),
+        _not_send: PhantomData,
+    }
+}
+
+/// Guard restoring the previous buffer of the current thread when dropped.
+///
+/// Created by [`buffer_scope()`].
+#[must_use = "the buffer is restored when the guard is dropped"]
+pub struct BufferScope {
+    previous: Option<LogId>,
+    /// The override is thread-local, so it must be restored by its thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for BufferScope {
+    fn drop(&mut self) {
+        let _ = SCOPED_BUFFER.try_with(|buffer| buffer.set(self.previous));
+    }
+}
+
+/// Buffer of the innermost active [`BufferScope`] of the current thread.
+pub(crate) fn current() -> Option<LogId> {
+    SCOPED_BUFFER.try_with(Cell::get).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn scope_overrides_buffer_until_dropped() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_log_buffer(LogId::System),
+        );
+        let log =
+            |msg: &str| logger.log(&test::record(Level::Info, "", None, format_args!("{msg}")));
+
+        {
+            let _crash = buffer_scope(LogId::Crash);
+            log("crash");
+            {
+                let _radio = buffer_scope(LogId::Radio);
+                log("radio");
+            }
+            log("crash again");
+        }
+        log("system");
+
+        let buf_ids: Vec<_> = take_emitted().into_iter().map(|e| e.buf_id).collect();
+        assert_eq!(
+            buf_ids,
+            [
+                Some(LogId::Crash),
+                Some(LogId::Radio),
+                Some(LogId::Crash),
+                Some(LogId::System)
+            ]
+        );
+    }
+}
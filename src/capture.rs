@@ -0,0 +1,199 @@
+//! Capture of the emitted logs, for tests.
+
+use super::*;
+use std::cell::RefCell;
+
+/// A log message captured by [`test_capture()`], as it would have been sent
+/// to the Android logging system.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CapturedRecord {
+    /// Buffer the message is logged to, `None` for the default one.
+    pub buf_id: Option<LogId>,
+    /// Level of the priority the message is logged with.
+    pub level: Level,
+    /// Tag of the message.
+    pub tag: String,
+    /// The message itself.
+    pub msg: String,
+}
+
+thread_local! {
+    /// Stack of the active captures of the current thread, innermost last.
+    static CAPTURES: RefCell<Vec<Vec<CapturedRecord>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f`, capturing the log messages it emits instead of sending them to
+/// the Android logging system, and returns them.
+///
+/// This captures the final messages, that is after filtering, formatting
+/// and splitting, emitted through any android logger (including the global
+/// one installed with [`init_once()`]) and [`write_cstr()`]. It works on any
+/// target, so tests of code logging through the `log` facade can check what
+/// it logs. Captures are thread-local: only the messages emitted by the
+/// current thread are captured, so tests running in parallel don't see each
+/// other's messages, but neither are the messages of threads spawned by `f`.
+///
+/// Captures may be nested, in which case messages are only captured by the
+/// innermost one.
+///
+/// ```
+/// use log::LevelFilter;
+/// use android_logger::Config;
+///
+/// android_logger::init_once(Config::default().with_max_level(LevelFilter::Info));
+///
+/// let captured = android_logger::test_capture(|| {
+///     log::info!("hello");
+///     log::debug!("not enabled");
+/// });
+///
+/// assert_eq!(captured.len(), 1);
+/// assert_eq!(captured[0].msg, "hello");
+/// ```
+pub fn test_capture<F: FnOnce()>(f: F) -> Vec<CapturedRecord> {
+    /// Ends the capture, even if `f` panics.
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            CAPTURES.with_borrow_mut(Vec::pop);
+        }
+    }
+
+    CAPTURES.with_borrow_mut(|captures| captures.push(Vec::new()));
+    let guard = Guard;
+    f();
+    let captured = CAPTURES.with_borrow_mut(|captures| mem::take(captures.last_mut().unwrap()));
+    drop(guard);
+    captured
+}
+
+/// Runs `f`, panicking if it emits any log message.
+///
+/// This checks that a code path stays silent (e.g. that a hot loop doesn't
+/// log), with the same scope as [`test_capture()`]: only the messages
+/// emitted by the current thread are considered. The panic message lists the
+/// emitted messages.
+///
+/// ```
+/// android_logger::assert_no_logs(|| {
+///     let _ = 1 + 1;
+/// });
+/// ```
+#[track_caller]
+pub fn assert_no_logs<F: FnOnce()>(f: F) {
+    let captured = test_capture(f);
+    if !captured.is_empty() {
+        panic!("expected no logs, got: {captured:#?}");
+    }
+}
+
+/// Adds the message built by `record` to the innermost capture, if any.
+///
+/// Returns whether the message was captured, in which case it must not be
+/// sent to the Android logging system.
+pub(crate) fn try_capture(record: impl FnOnce() -> CapturedRecord) -> bool {
+    // Messages logged while the thread exits (see
+    // `Config::log_thread_lifecycle()`) can't be captured anymore.
+    CAPTURES
+        .try_with(|captures| match captures.borrow_mut().last_mut() {
+            Some(capture) => {
+                capture.push(record());
+                true
+            }
+            None => false,
+        })
+        .unwrap_or(false)
+}
+
+/// Checks whether the messages of the current thread are being captured,
+/// in which case they must not be copied anywhere either.
+#[cfg(unix)]
+pub(crate) fn is_capturing() -> bool {
+    CAPTURES
+        .try_with(|captures| !captures.borrow().is_empty())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn log(logger: &AndroidLogger, msg: &str) {
+        logger.log(
+            &Record::builder()
+                .level(Level::Warn)
+                .module_path(Some("app"))
+                .args(format_args!("{msg}"))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn captures_emitted_messages() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        let captured = test_capture(|| {
+            log(&logger, "first");
+            log(&logger, "second");
+        });
+
+        assert_eq!(
+            captured,
+            ["first", "second"].map(|msg| CapturedRecord {
+                buf_id: None,
+                level: Level::Warn,
+                tag: "app".to_owned(),
+                msg: msg.to_owned(),
+            })
+        );
+        assert!(take_emitted().is_empty());
+    }
+
+    #[test]
+    fn nested_captures() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        let mut inner = Vec::new();
+        let outer = test_capture(|| {
+            log(&logger, "outer");
+            inner = test_capture(|| log(&logger, "inner"));
+        });
+        log(&logger, "uncaptured");
+
+        assert_eq!(outer.len(), 1);
+        assert_eq!(outer[0].msg, "outer");
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].msg, "inner");
+        assert_eq!(take_emitted().len(), 1);
+    }
+
+    #[test]
+    fn silent_closure_has_no_logs() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Error));
+
+        assert_no_logs(|| log(&logger, "filtered"));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected no logs, got")]
+    fn logging_closure_has_logs() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        assert_no_logs(|| log(&logger, "noisy"));
+    }
+
+    #[test]
+    fn other_threads_are_not_captured() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        let captured = test_capture(|| {
+            std::thread::scope(|scope| {
+                scope.spawn(|| log(&logger, "elsewhere"));
+            });
+        });
+
+        assert!(captured.is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+//! Defeating the collapsing of identical messages by logd.
+
+use super::*;
+use dedup::message_hash;
+
+/// Numbers the consecutive repeats of a message, see
+/// [`Config::defeat_chatty()`].
+#[derive(Clone, Default)]
+pub(crate) struct Chatty {
+    /// Hash of the previous message, and how many times it was repeated.
+    previous: State<Mutex<Option<(u64, u64)>>>,
+}
+
+impl Chatty {
+    /// Returns the number of the repeat of the previous message `record` is,
+    /// if it is one.
+    pub(crate) fn repeat(&self, record: &Record) -> Option<u64> {
+        let hash = message_hash(record);
+        let mut previous = self.previous.lock().unwrap_or_else(PoisonError::into_inner);
+        match &mut *previous {
+            Some((previous, repeats)) if *previous == hash => {
+                *repeats += 1;
+                Some(*repeats)
+            }
+            _ => {
+                *previous = Some((hash, 0));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::log_msgs;
+
+    #[test]
+    fn identical_messages_get_distinct_tokens() {
+        let tick = || test::record(Level::Info, "app", None, format_args!("tick"));
+        let tock = || test::record(Level::Info, "app", None, format_args!("tock"));
+
+        let msgs = log_msgs(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .defeat_chatty(true),
+            &[tick(), tick(), tick(), tock(), tick()],
+        );
+
+        assert_eq!(msgs, ["tick", "tick #1", "tick #2", "tock", "tick"]);
+    }
+
+    #[test]
+    fn structured_formats_get_a_repeat_field() {
+        let tick = || test::record(Level::Info, "app", None, format_args!("tick"));
+
+        let msgs = log_msgs(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_json()
+                .defeat_chatty(true),
+            &[tick(), tick()],
+        );
+        assert_eq!(
+            msgs,
+            [
+                r#"{"level":"INFO","target":"app","msg":"tick"}"#,
+                r#"{"level":"INFO","target":"app","msg":"tick","repeat":1}"#,
+            ]
+        );
+
+        let msgs = log_msgs(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_logfmt()
+                .defeat_chatty(true),
+            &[tick(), tick()],
+        );
+        assert_eq!(
+            msgs,
+            [
+                r#"level=info target=app msg="tick""#,
+                r#"level=info target=app msg="tick" repeat=1"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Source of the current time for the time-based features.
+
+use super::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time, for time-based features such as timestamps
+/// ([`Config::with_timestamp()`]), dedup windows ([`Config::dedup_window()`])
+/// and [`timed()`] scopes.
+///
+/// The default [`SystemClock`] reads the system clocks. Tests can set a
+/// clock they advance manually with [`Config::with_clock()`], to check these
+/// features deterministically.
+pub trait Clock: Sync + Send {
+    /// Returns the current monotonic time, for measuring durations.
+    fn now(&self) -> Instant;
+
+    /// Returns the current wall-clock time, for timestamps.
+    fn system_time(&self) -> SystemTime;
+}
+
+/// A [`Clock`] reading [`Instant::now()`] and [`SystemTime::now()`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl AndroidLogger {
+    /// Logs `msg` with the timestamp `time`, see [`log_with_time()`].
+    pub fn log_with_time(&self, time: SystemTime, level: Level, tag: &str, msg: &str) {
+        self.emit(
+            &Record::builder()
+                .level(level)
+                .target(tag)
+                .args(format_args!("{msg}"))
+                .build(),
+            Some(tag),
+            Some(time),
+        );
+    }
+}
+
+/// Logs `msg` through the global android logger, at the given `level` and
+/// under `tag`, as if it was logged at `time`.
+///
+/// This is meant for replaying events which happened earlier, e.g. received
+/// from another device: with [`Config::with_timestamp()`], the timestamp
+/// prefix is `time` instead of the current time. Logcat's own timestamp of the
+/// message is still the time it is logged at.
+///
+/// ```
+/// # use log::Level;
+/// # use std::time::{Duration, SystemTime};
+/// let happened = SystemTime::now() - Duration::from_secs(60);
+/// android_logger::log_with_time(happened, Level::Info, "my_app", "sensor tripped");
+/// ```
+pub fn log_with_time(time: SystemTime, level: Level, tag: &str, msg: &str) {
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .log_with_time(time, level, tag, msg)
+}
+
+/// Time of the previous record logged with [`Config::with_inter_log_delta()`].
+static PREVIOUS_LOG: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Writes the time elapsed since the previous record logged with
+/// [`Config::with_inter_log_delta()`], e.g. `(+12ms) `, and records `now` as
+/// the time of the previous record.
+///
+/// Nothing is written for the first record.
+pub(crate) fn write_inter_log_delta(f: &mut dyn fmt::Write, now: Instant) -> fmt::Result {
+    let previous = PREVIOUS_LOG
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(now);
+    match previous {
+        Some(previous) => write!(
+            f,
+            "(+{}ms) ",
+            now.saturating_duration_since(previous).as_millis()
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Writes `time` as an RFC 3339 UTC timestamp with milliseconds, e.g.
+/// `2023-11-14T22:13:20.123Z`.
+///
+/// Times before the Unix epoch are written as the epoch itself.
+pub(crate) fn write_timestamp(f: &mut dyn fmt::Write, time: SystemTime) -> fmt::Result {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days(secs / 86400);
+    let secs_of_day = secs % 86400;
+    write!(
+        f,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Converts a number of days since the Unix epoch into a (year, month, day)
+/// date of the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+    use std::sync::{Arc, Mutex};
+
+    /// A [`Clock`] standing still until advanced.
+    #[derive(Clone)]
+    struct FakeClock {
+        start: Instant,
+        elapsed: Arc<Mutex<Duration>>,
+    }
+
+    impl FakeClock {
+        fn new() -> FakeClock {
+            FakeClock {
+                start: Instant::now(),
+                elapsed: Arc::default(),
+            }
+        }
+
+        fn advance(&self, by: Duration) {
+            *self.elapsed.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.start + *self.elapsed.lock().unwrap()
+        }
+
+        fn system_time(&self) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000) + *self.elapsed.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn timestamps() {
+        let format = |time: SystemTime| {
+            let mut s = String::new();
+            write_timestamp(&mut s, time).unwrap();
+            s
+        };
+
+        assert_eq!(format(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format(UNIX_EPOCH + Duration::from_millis(951_825_600_042)),
+            "2000-02-29T12:00:00.042Z"
+        );
+        assert_eq!(
+            format(UNIX_EPOCH - Duration::from_secs(1)),
+            "1970-01-01T00:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn timestamp_prefix_uses_injected_clock() {
+        let clock = FakeClock::new();
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_timestamp(true)
+                .with_clock(clock.clone()),
+        );
+        let log = || {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("tick"))
+                    .build(),
+            )
+        };
+
+        log();
+        clock.advance(Duration::from_millis(1500));
+        log();
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(
+            emitted,
+            [
+                "2023-11-14T22:13:20.000Z tick",
+                "2023-11-14T22:13:21.500Z tick"
+            ]
+        );
+    }
+
+    #[test]
+    fn inter_log_delta_prefix() {
+        let clock = FakeClock::new();
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_inter_log_delta(true)
+                .with_clock(clock.clone()),
+        );
+        let log = || {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("tick"))
+                    .build(),
+            )
+        };
+
+        // The gap before the first record depends on what was logged before.
+        log();
+        clock.advance(Duration::from_millis(12));
+        log();
+        log();
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted[1..], ["(+12ms) tick", "(+0ms) tick"]);
+    }
+
+    #[test]
+    fn supplied_time_replaces_now() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_timestamp(true)
+                .with_clock(FakeClock::new()),
+        );
+
+        logger.log_with_time(
+            UNIX_EPOCH + Duration::from_secs(951_825_600),
+            Level::Info,
+            "replay",
+            "event",
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted[0].tag, "replay");
+        assert_eq!(emitted[0].msg, "2000-02-29T12:00:00.000Z event");
+    }
+}
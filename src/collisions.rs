@@ -0,0 +1,79 @@
+//! Detection of tags shared by several modules.
+
+use super::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Number of distinct tags tracked, later ones aren't checked.
+const CAPACITY: usize = 1024;
+
+/// The module paths tags were resolved from.
+#[derive(Clone, Default)]
+pub(crate) struct TagCollisions {
+    /// The module path of each tag, or `None` once a collision was reported.
+    tags: State<Mutex<HashMap<Vec<u8>, Option<String>>>>,
+}
+
+impl TagCollisions {
+    /// Records that `tag` was resolved from `module_path`.
+    ///
+    /// Returns the other module path resolving to `tag`, the first time one
+    /// is seen.
+    pub(crate) fn check(&self, tag: &[u8], module_path: &str) -> Option<String> {
+        let mut tags = self.tags.lock().unwrap_or_else(PoisonError::into_inner);
+        match tags.get_mut(tag) {
+            Some(seen) => match seen {
+                Some(seen_path) if seen_path != module_path => seen.take(),
+                _ => None,
+            },
+            None => {
+                if tags.len() < CAPACITY {
+                    tags.insert(tag.to_owned(), Some(module_path.to_owned()));
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn collision_is_reported_once() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .warn_tag_collisions(true),
+        );
+        let log = |module_path| {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .module_path(Some(module_path))
+                    .args(format_args!("msg"))
+                    .build(),
+            )
+        };
+
+        for _ in 0..2 {
+            log("my_crate::some_long_module::first");
+            log("my_crate::some_long_module::second");
+            log("my_crate::short");
+        }
+
+        let warnings: Vec<_> = take_emitted()
+            .into_iter()
+            .filter(|e| e.tag == INTERNAL_TAG.to_str().unwrap())
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].priority, Level::Warn);
+        assert_eq!(
+            warnings[0].msg,
+            "tag `my_crate::some_long_m..` is shared by modules \
+             `my_crate::some_long_module::first` and `my_crate::some_long_module::second`"
+        );
+    }
+}
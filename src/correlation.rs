@@ -0,0 +1,86 @@
+//! Correlation ids of the current thread.
+
+use super::*;
+use std::cell::RefCell;
+
+/// Maximum length of the correlation id in a message, in bytes. Longer ids
+/// are truncated, so they don't eat into the length of messages.
+const MAX_CORRELATION_ID_LEN: usize = 64;
+
+thread_local! {
+    /// Correlation id of the current thread.
+    static CORRELATION_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the correlation id (e.g. a request id) of the current thread, or
+/// clears it with `None`.
+///
+/// With [`Config::with_correlation_id()`], messages logged by this thread are
+/// then prefixed with `[req=<id>] `, so that the messages of a single request
+/// can be found together. Ids longer than 64 bytes are truncated.
+///
+/// ```
+/// android_logger::set_correlation_id(Some("abc123".to_owned()));
+/// // log::info!("handling request") logs "[req=abc123] handling request"
+/// android_logger::set_correlation_id(None);
+/// ```
+pub fn set_correlation_id(id: Option<String>) {
+    let _ = CORRELATION_ID.try_with(|current| *current.borrow_mut() = id);
+}
+
+/// Writes the `[req=<id>] ` prefix, if the current thread has a correlation
+/// id.
+pub(crate) fn write_prefix(f: &mut dyn fmt::Write) -> fmt::Result {
+    CORRELATION_ID
+        .try_with(|id| match &*id.borrow() {
+            Some(id) => {
+                let mut len = id.len().min(MAX_CORRELATION_ID_LEN);
+                while !id.is_char_boundary(len) {
+                    len -= 1;
+                }
+                write!(f, "[req={}] ", &id[..len])
+            }
+            None => Ok(()),
+        })
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn messages_are_prefixed_with_correlation_id() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_correlation_id(true),
+        );
+        let log = || {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("handling"))
+                    .build(),
+            )
+        };
+
+        set_correlation_id(Some("abc123".to_owned()));
+        log();
+        set_correlation_id(Some("é".repeat(40)));
+        log();
+        set_correlation_id(None);
+        log();
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(
+            emitted,
+            [
+                "[req=abc123] handling".to_owned(),
+                format!("[req={}] handling", "é".repeat(32)),
+                "handling".to_owned(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Suppression of recently seen messages.
+
+use super::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::Hasher;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Outcome of checking a record against a [`DedupWindow`].
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum Dedup {
+    /// The message was seen within the window and must be dropped.
+    Suppressed,
+
+    /// The message must be emitted. `suppressed` holds the number of
+    /// messages dropped since the last summary, if a new summary is due.
+    Emit { suppressed: Option<u64> },
+}
+
+/// Bounded set of the hashes of recently emitted messages.
+#[derive(Clone)]
+pub(crate) struct DedupWindow {
+    window: Duration,
+    capacity: usize,
+    state: State<Mutex<DedupState>>,
+}
+
+#[derive(Default)]
+struct DedupState {
+    /// Hashes of emitted messages, ordered by the time they were first seen.
+    recent: VecDeque<(u64, Instant)>,
+    suppressed: u64,
+    last_summary: Option<Instant>,
+}
+
+impl DedupWindow {
+    pub(crate) fn new(window: Duration, capacity: usize) -> DedupWindow {
+        DedupWindow {
+            window,
+            capacity,
+            state: State::default(),
+        }
+    }
+
+    pub(crate) fn check(&self, record: &Record, now: Instant) -> Dedup {
+        self.check_hash(message_hash(record), now)
+    }
+
+    fn check_hash(&self, hash: u64, now: Instant) -> Dedup {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while let Some(&(_, seen)) = state.recent.front() {
+            if now.saturating_duration_since(seen) < self.window {
+                break;
+            }
+            state.recent.pop_front();
+        }
+
+        if state.recent.iter().any(|&(recent, _)| recent == hash) {
+            state.suppressed += 1;
+            return Dedup::Suppressed;
+        }
+
+        if self.capacity > 0 {
+            if state.recent.len() == self.capacity {
+                state.recent.pop_front();
+            }
+            state.recent.push_back((hash, now));
+        }
+
+        let summary_due = state
+            .last_summary
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.window);
+        let suppressed = if state.suppressed > 0 && summary_due {
+            state.last_summary = Some(now);
+            Some(mem::take(&mut state.suppressed))
+        } else {
+            None
+        };
+        Dedup::Emit { suppressed }
+    }
+}
+
+/// Hashes the level and message of `record`, without allocating.
+pub(crate) fn message_hash(record: &Record) -> u64 {
+    struct HashWriter(DefaultHasher);
+
+    impl fmt::Write for HashWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut hasher = HashWriter(DefaultHasher::new());
+    hasher.0.write_usize(record.level() as usize);
+    let _ = fmt::write(&mut hasher, *record.args());
+    hasher.0.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{log_msgs, take_emitted};
+
+    #[test]
+    fn interleaved_messages_are_suppressed() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .dedup_window(Duration::from_secs(60), 8),
+        );
+
+        for _ in 0..3 {
+            for msg in ["first", "second"] {
+                logger.log(
+                    &Record::builder()
+                        .level(Level::Info)
+                        .args(format_args!("{msg}"))
+                        .build(),
+                );
+            }
+        }
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["first", "second"]);
+    }
+
+    #[test]
+    fn messages_reappear_after_window() {
+        let dedup = DedupWindow::new(Duration::from_secs(1), 8);
+        let start = Instant::now();
+
+        assert_eq!(dedup.check_hash(1, start), Dedup::Emit { suppressed: None });
+        assert_eq!(dedup.check_hash(1, start), Dedup::Suppressed);
+        assert_eq!(
+            dedup.check_hash(1, start + Duration::from_millis(500)),
+            Dedup::Suppressed
+        );
+        assert_eq!(
+            dedup.check_hash(1, start + Duration::from_secs(1)),
+            Dedup::Emit {
+                suppressed: Some(2)
+            }
+        );
+    }
+
+    #[test]
+    fn summary_is_emitted_at_most_once_per_window() {
+        let dedup = DedupWindow::new(Duration::from_secs(1), 8);
+        let start = Instant::now();
+
+        dedup.check_hash(1, start);
+        dedup.check_hash(1, start);
+        assert_eq!(
+            dedup.check_hash(2, start),
+            Dedup::Emit {
+                suppressed: Some(1)
+            }
+        );
+        dedup.check_hash(1, start);
+        assert_eq!(dedup.check_hash(3, start), Dedup::Emit { suppressed: None });
+        assert_eq!(
+            dedup.check_hash(4, start + Duration::from_secs(1)),
+            Dedup::Emit {
+                suppressed: Some(1)
+            }
+        );
+    }
+
+    #[test]
+    fn capacity_bounds_recent_messages() {
+        let dedup = DedupWindow::new(Duration::from_secs(60), 2);
+        let now = Instant::now();
+
+        for hash in [1, 2, 3] {
+            dedup.check_hash(hash, now);
+        }
+
+        // The oldest message was evicted to make room for the newest one.
+        assert!(matches!(dedup.check_hash(1, now), Dedup::Emit { .. }));
+        assert_eq!(dedup.check_hash(3, now), Dedup::Suppressed);
+    }
+
+    #[test]
+    fn capacity_is_not_preallocated() {
+        let dedup = DedupWindow::new(Duration::from_secs(1), usize::MAX);
+
+        assert_eq!(
+            dedup.check_hash(1, Instant::now()),
+            Dedup::Emit { suppressed: None }
+        );
+    }
+
+    #[test]
+    fn summary_is_in_active_format() {
+        let a = || test::record(Level::Info, "app", None, format_args!("a"));
+        let b = || test::record(Level::Info, "app", None, format_args!("b"));
+        let msgs = log_msgs(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_json()
+                .dedup_window(Duration::from_secs(60), 8),
+            &[a(), a(), b()],
+        );
+
+        assert_eq!(
+            msgs,
+            [
+                r#"{"level":"INFO","target":"app","msg":"a"}"#,
+                r#"{"level":"INFO","target":"app","msg":"suppressed 1 duplicate messages"}"#,
+                r#"{"level":"INFO","target":"app","msg":"b"}"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Logging of the changes of numeric values.
+
+use super::*;
+use std::collections::VecDeque;
+
+/// Number of distinct values whose previous value is kept.
+const CAPACITY: usize = 256;
+
+/// The previous values logged with [`AndroidLogger::log_delta()`].
+#[derive(Default)]
+pub(crate) struct Deltas {
+    /// Tags, labels and previous values, least recently logged first.
+    values: Mutex<VecDeque<(String, String, f64)>>,
+}
+
+impl Deltas {
+    /// Records `value` as the new value of `label` under `tag`, returning the
+    /// previous one.
+    fn update(&self, tag: &str, label: &str, value: f64) -> Option<f64> {
+        let mut values = self.values.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let previous = match values.iter().position(|(t, l, _)| t == tag && l == label) {
+            Some(i) => values.remove(i).map(|(_, _, previous)| previous),
+            None => {
+                if values.len() == CAPACITY {
+                    values.pop_front();
+                }
+                None
+            }
+        };
+        values.push_back((tag.to_owned(), label.to_owned(), value));
+        previous
+    }
+}
+
+impl AndroidLogger {
+    /// Logs `value` with its change since the previous call with the same
+    /// `tag` and `label`, see [`log_delta()`].
+    pub fn log_delta(&self, tag: &str, label: &str, value: f64) {
+        if !self.enabled(&Metadata::builder().level(Level::Info).target(tag).build()) {
+            return;
+        }
+
+        let delta = match self.deltas.update(tag, label, value) {
+            Some(previous) => format!(" (Δ{:+})", value - previous),
+            None => String::new(),
+        };
+        self.emit(
+            &Record::builder()
+                .level(Level::Info)
+                .target(tag)
+                .args(format_args!("{label}: {value}{delta}"))
+                .build(),
+            Some(tag),
+            None,
+        );
+    }
+}
+
+/// Logs `value` through the global android logger, at the info level and
+/// under `tag`, along with its change since the previous call with the same
+/// `tag` and `label`, e.g. `frames: 120 (Δ+12)`.
+///
+/// This allows watching counters in logcat. The first value of a label is
+/// logged without change. The previous values of the 256 most recently
+/// logged labels are kept: a label logged after more than 256 other ones is
+/// logged as if it was new.
+///
+/// ```
+/// android_logger::log_delta("my_app", "cache size", 120.0);
+/// android_logger::log_delta("my_app", "cache size", 132.0); // cache size: 132 (Δ+12)
+/// ```
+pub fn log_delta(tag: &str, label: &str, value: f64) {
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .log_delta(tag, label, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn delta_to_previous_value_of_label() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        logger.log_delta("metrics", "frames", 100.0);
+        logger.log_delta("metrics", "frames", 112.0);
+        logger.log_delta("metrics", "memory", 1.5);
+        logger.log_delta("other", "frames", 7.0);
+        logger.log_delta("metrics", "frames", 110.5);
+        logger.log_delta("metrics", "memory", 1.5);
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(
+            emitted,
+            [
+                "frames: 100",
+                "frames: 112 (Δ+12)",
+                "memory: 1.5",
+                "frames: 7",
+                "frames: 110.5 (Δ-1.5)",
+                "memory: 1.5 (Δ+0)",
+            ]
+        );
+    }
+
+    #[test]
+    fn oldest_labels_are_forgotten() {
+        let deltas = Deltas::default();
+
+        for i in 0..=CAPACITY {
+            deltas.update("tag", &i.to_string(), 1.0);
+        }
+
+        assert_eq!(deltas.update("tag", "0", 2.0), None);
+        assert_eq!(deltas.update("tag", &CAPACITY.to_string(), 2.0), Some(1.0));
+    }
+}
@@ -0,0 +1,115 @@
+//! Tracking of filter directives, so they can be rendered back.
+
+use super::*;
+
+/// The directives of a filter, in the `RUST_LOG` syntax.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Directives {
+    /// Maximum levels, by module. `None` is the level of all modules.
+    levels: Vec<(Option<String>, LevelFilter)>,
+    /// Pattern messages must match.
+    regex: Option<String>,
+    /// Directives which could not be parsed, and were ignored.
+    invalid: Vec<String>,
+}
+
+impl Directives {
+    /// Sets the maximum level of `module`, replacing any previous one.
+    pub(crate) fn insert(&mut self, module: Option<&str>, level: LevelFilter) {
+        match self.levels.iter_mut().find(|(m, _)| m.as_deref() == module) {
+            Some((_, old)) => *old = level,
+            None => self.levels.push((module.map(str::to_owned), level)),
+        }
+    }
+
+    /// Adds the directives of a `RUST_LOG`-style string, e.g.
+    /// `info,my_crate=debug/pattern`.
+    ///
+    /// Like [`FilterBuilder::parse()`], invalid directives are ignored, but
+    /// they are kept in [`Directives::invalid()`].
+    pub(crate) fn parse(&mut self, filters: &str) {
+        let (levels, regex) = match filters.split_once('/') {
+            Some((levels, regex)) => (levels, Some(regex)),
+            None => (filters, None),
+        };
+
+        for directive in levels.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+            match directive.split_once('=') {
+                Some((module, level)) => match level.trim().parse() {
+                    Ok(level) => self.insert(Some(module.trim()), level),
+                    Err(_) => self.invalid.push(directive.to_owned()),
+                },
+                None => match directive.parse() {
+                    Ok(level) => self.insert(None, level),
+                    Err(_) => self.insert(Some(directive), LevelFilter::Trace),
+                },
+            }
+        }
+
+        if let Some(regex) = regex {
+            self.regex = Some(regex.to_owned());
+        }
+    }
+
+    /// Returns the directives which could not be parsed.
+    pub(crate) fn invalid(&self) -> &[String] {
+        &self.invalid
+    }
+
+    /// Builds the filter applying these directives.
+    pub(crate) fn build(&self) -> Filter {
+        FilterBuilder::new().parse(&self.to_string()).build()
+    }
+}
+
+impl fmt::Display for Directives {
+    /// Renders the level of all modules first, then the ones of each module
+    /// in the order they were first set, and finally the pattern.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let global = self.levels.iter().filter(|(m, _)| m.is_none());
+        let modules = self.levels.iter().filter(|(m, _)| m.is_some());
+
+        for (i, (module, level)) in global.chain(modules).enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            let level = level.as_str().to_ascii_lowercase();
+            match module {
+                Some(module) => write!(f, "{module}={level}")?,
+                None => f.write_str(&level)?,
+            }
+        }
+
+        if let Some(regex) = &self.regex {
+            write!(f, "/{regex}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_directives_override_earlier_ones() {
+        let mut directives = Directives::default();
+        directives.parse("my_crate=debug,warn");
+        directives.insert(Some("my_crate"), LevelFilter::Trace);
+        directives.insert(None, LevelFilter::Info);
+
+        assert_eq!(directives.to_string(), "info,my_crate=trace");
+    }
+
+    #[test]
+    fn parse_directives() {
+        let mut directives = Directives::default();
+        directives.parse(" my_crate , other=ERROR,,bad=loud/^net");
+
+        assert_eq!(directives.to_string(), "my_crate=trace,other=error/^net");
+        assert_eq!(directives.invalid(), ["bad=loud"]);
+    }
+}
@@ -0,0 +1,102 @@
+//! Buffering of the records logged before initialization.
+
+use super::*;
+use std::collections::VecDeque;
+
+/// A record logged before initialization, owning its data.
+struct EarlyRecord {
+    level: Level,
+    target: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    msg: String,
+}
+
+/// The records logged before initialization, oldest first.
+struct EarlyBuffer {
+    capacity: usize,
+    records: VecDeque<EarlyRecord>,
+}
+
+/// Set by [`enable_early_buffering()`], taken by [`init_once()`].
+static EARLY_BUFFER: Mutex<Option<EarlyBuffer>> = Mutex::new(None);
+
+/// Buffers the records sent to [`log()`] before [`init_once()`] is called,
+/// instead of logging them with the default configuration, and replays them
+/// through the configured logger once it is.
+///
+/// This keeps the diagnostics of early startup (e.g. of static
+/// initialization) which the default configuration would filter out, or
+/// format differently. At most `capacity` records are kept: when full, the
+/// oldest record is dropped for each new one. Records logged by other means
+/// than [`log()`], e.g. the `log` macros before a logger is installed, aren't
+/// buffered.
+///
+/// Replayed records go through the configured filters and formatting, as if
+/// they were logged at that time (e.g. with a later timestamp).
+///
+/// Does nothing if the global android logger is already initialized.
+pub fn enable_early_buffering(capacity: usize) {
+    let mut early = EARLY_BUFFER.lock().unwrap_or_else(PoisonError::into_inner);
+    if ANDROID_LOGGER.get().is_none() {
+        *early = Some(EarlyBuffer {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        });
+    }
+}
+
+/// Buffers `record` if early buffering is enabled and the global android
+/// logger isn't initialized yet.
+///
+/// Returns whether the record was buffered.
+pub(crate) fn try_buffer(record: &Record) -> bool {
+    let mut early = EARLY_BUFFER.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(buffer) = early.as_mut() else {
+        return false;
+    };
+    if ANDROID_LOGGER.get().is_some() {
+        return false;
+    }
+
+    if buffer.capacity == 0 {
+        return true;
+    }
+    if buffer.records.len() == buffer.capacity {
+        buffer.records.pop_front();
+    }
+    buffer.records.push_back(EarlyRecord {
+        level: record.level(),
+        target: record.target().to_owned(),
+        module_path: record.module_path().map(str::to_owned),
+        file: record.file().map(str::to_owned),
+        line: record.line(),
+        msg: record.args().to_string(),
+    });
+    true
+}
+
+/// Logs the buffered records through `logger`, and stops buffering.
+pub(crate) fn replay(logger: &AndroidLogger) {
+    let Some(buffer) = EARLY_BUFFER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+    else {
+        return;
+    };
+
+    for record in buffer.records {
+        logger.log(
+            &Record::builder()
+                .level(record.level)
+                .target(&record.target)
+                .module_path(record.module_path.as_deref())
+                .file(record.file.as_deref())
+                .line(record.line)
+                .args(format_args!("{}", record.msg))
+                .build(),
+        );
+    }
+}
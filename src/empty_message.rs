@@ -0,0 +1,151 @@
+//! Handling of records without a message.
+
+use super::*;
+
+/// What is logged for records whose message is empty, e.g. records only
+/// made of key-values, see [`Config::empty_message_behavior()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum EmptyMessage {
+    /// The record is logged as is: only its key-values in structured formats
+    /// (e.g. [`Config::format_json()`]) and with `Config::pretty_kv()`.
+    /// Otherwise, only its prefixes (e.g. of [`Config::with_timestamp()`])
+    /// are logged, if any.
+    #[default]
+    KvOnly,
+
+    /// The message is replaced with this text, and the record logged.
+    Placeholder(String),
+
+    /// The record is dropped.
+    Skip,
+}
+
+/// Whether `args` format to an empty message.
+///
+/// This stops formatting at the first byte written.
+fn is_empty(args: &fmt::Arguments) -> bool {
+    struct Probe;
+
+    impl fmt::Write for Probe {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if s.is_empty() {
+                Ok(())
+            } else {
+                Err(fmt::Error)
+            }
+        }
+    }
+
+    match args.as_str() {
+        Some(s) => s.is_empty(),
+        None => fmt::write(&mut Probe, *args).is_ok(),
+    }
+}
+
+impl AndroidLogger {
+    /// Applies [`Config::empty_message_behavior()`] to `record`.
+    ///
+    /// Returns whether `record` was handled, and mustn't be logged as is.
+    pub(crate) fn handle_empty_message(
+        &self,
+        record: &Record,
+        tag: Option<&str>,
+        time: Option<SystemTime>,
+    ) -> bool {
+        let behavior = &self.config().empty_message;
+        if *behavior == EmptyMessage::KvOnly || !is_empty(record.args()) {
+            return false;
+        }
+
+        match behavior {
+            // An empty placeholder would be replaced again, and again.
+            EmptyMessage::Placeholder(placeholder) if !placeholder.is_empty() => {
+                let mut builder = Record::builder();
+                builder
+                    .metadata(record.metadata().clone())
+                    .module_path(record.module_path())
+                    .file(record.file())
+                    .line(record.line());
+                #[cfg(feature = "kv")]
+                builder.key_values(record.key_values());
+                self.emit(
+                    &builder.args(format_args!("{placeholder}")).build(),
+                    tag,
+                    time,
+                );
+                true
+            }
+            EmptyMessage::KvOnly | EmptyMessage::Placeholder(_) => false,
+            EmptyMessage::Skip => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::log_msgs;
+
+    #[test]
+    fn empty_messages_are_detected() {
+        let world = "";
+        assert!(is_empty(&format_args!("")));
+        assert!(is_empty(&format_args!("{world}")));
+        assert!(!is_empty(&format_args!("hello {world}")));
+    }
+
+    #[test]
+    fn placeholder_replaces_empty_message() {
+        let log = |behavior: EmptyMessage| {
+            let empty = "";
+            log_msgs(
+                Config::default()
+                    .with_max_level(LevelFilter::Info)
+                    .empty_message_behavior(behavior),
+                &[test::record(
+                    Level::Info,
+                    "app",
+                    None,
+                    format_args!("{empty}"),
+                )],
+            )
+        };
+
+        assert!(log(EmptyMessage::KvOnly).is_empty());
+        assert_eq!(
+            log(EmptyMessage::Placeholder("<empty>".to_owned())),
+            ["<empty>"]
+        );
+        assert!(log(EmptyMessage::Skip).is_empty());
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn empty_message_behaviors() {
+        let log = |behavior: EmptyMessage| {
+            let kvs = [("user", log::kv::Value::from("alice"))];
+            log_msgs(
+                Config::default()
+                    .with_max_level(LevelFilter::Info)
+                    .format_logfmt()
+                    .empty_message_behavior(behavior),
+                &[Record::builder()
+                    .level(Level::Info)
+                    .target("app")
+                    .args(format_args!(""))
+                    .key_values(&kvs)
+                    .build()],
+            )
+        };
+
+        assert_eq!(
+            log(EmptyMessage::KvOnly),
+            [r#"level=info target=app msg="" user=alice"#]
+        );
+        assert_eq!(
+            log(EmptyMessage::Placeholder("<no message>".to_owned())),
+            [r#"level=info target=app msg="<no message>" user=alice"#]
+        );
+        assert!(log(EmptyMessage::Skip).is_empty());
+    }
+}
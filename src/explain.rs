@@ -0,0 +1,43 @@
+//! Diagnostics of records dropped against the expectations of the filter.
+
+use super::*;
+
+impl AndroidLogger {
+    /// Explains why `record`, which wasn't enabled, was dropped, the first
+    /// time it's because of [`log::max_level()`] while the filter would have
+    /// allowed it, see [`Config::explain_drops()`].
+    pub(crate) fn explain_drop(&self, record: &Record) {
+        let config = self.config();
+        if !cfg!(debug_assertions)
+            || !config.explain_drops
+            || config.silence_internal
+            || config.log_level.is_some()
+            || self.shut_down.load(Ordering::Relaxed)
+        {
+            return;
+        }
+        let Some(filter) = &config.filter else {
+            return;
+        };
+        let overridden = self
+            .filter_override
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some();
+        if overridden || !filter.matches(record) {
+            return;
+        }
+
+        if !self.drop_explained.swap(true, Ordering::Relaxed) {
+            write_internal(
+                Level::Warn,
+                format_args!(
+                    "record at {} dropped by max_level={} though filter permits it; \
+                     call log::set_max_level higher",
+                    record.level(),
+                    log::max_level()
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Lifetime cap on the occurrences of each message.
+
+use super::*;
+use dedup::message_hash;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of distinct messages whose occurrences are counted.
+const CAPACITY: usize = 256;
+
+/// Outcome of checking a record against a [`FirstN`].
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum Occurrence {
+    /// The message was seen less than `n` times, and must be emitted.
+    Emit,
+
+    /// The message was seen `n` times already, this is the first occurrence
+    /// dropped.
+    FirstSuppressed,
+
+    /// The message was seen more than `n` times already, and must be dropped.
+    Suppressed,
+}
+
+/// Counts of the occurrences of recently seen messages.
+#[derive(Clone)]
+pub(crate) struct FirstN {
+    n: u32,
+    /// Hashes and occurrence counts of messages, least recently seen first.
+    counts: State<Mutex<VecDeque<(u64, u32)>>>,
+}
+
+impl FirstN {
+    pub(crate) fn new(n: u32) -> FirstN {
+        FirstN {
+            n,
+            counts: State::default(),
+        }
+    }
+
+    pub(crate) fn check(&self, record: &Record) -> Occurrence {
+        self.check_hash(message_hash(record))
+    }
+
+    fn check_hash(&self, hash: u64) -> Occurrence {
+        let mut counts = self.counts.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let count = match counts.iter().position(|&(seen, _)| seen == hash) {
+            Some(i) => counts.remove(i).map_or(0, |(_, count)| count),
+            None => {
+                if counts.len() == CAPACITY {
+                    counts.pop_front();
+                }
+                0
+            }
+        };
+        let count = count.saturating_add(1);
+        counts.push_back((hash, count));
+
+        match count.checked_sub(self.n) {
+            None | Some(0) => Occurrence::Emit,
+            Some(1) => Occurrence::FirstSuppressed,
+            Some(_) => Occurrence::Suppressed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{log_msgs, take_emitted};
+
+    #[test]
+    fn only_first_occurrences_are_logged() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .log_first_n(3),
+        );
+
+        for _ in 0..20 {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("starting up"))
+                    .build(),
+            );
+        }
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(
+            emitted,
+            [
+                "starting up",
+                "starting up",
+                "starting up",
+                "suppressing further occurrences of: starting up"
+            ]
+        );
+    }
+
+    #[test]
+    fn messages_are_counted_separately() {
+        let first_n = FirstN::new(1);
+
+        assert_eq!(first_n.check_hash(1), Occurrence::Emit);
+        assert_eq!(first_n.check_hash(2), Occurrence::Emit);
+        assert_eq!(first_n.check_hash(1), Occurrence::FirstSuppressed);
+        assert_eq!(first_n.check_hash(1), Occurrence::Suppressed);
+        assert_eq!(first_n.check_hash(2), Occurrence::FirstSuppressed);
+    }
+
+    #[test]
+    fn least_recently_seen_messages_are_forgotten() {
+        let first_n = FirstN::new(1);
+
+        for hash in 0..=CAPACITY as u64 {
+            assert_eq!(first_n.check_hash(hash), Occurrence::Emit);
+        }
+
+        // The first message was evicted to make room for the last one.
+        assert_eq!(first_n.check_hash(0), Occurrence::Emit);
+        assert_eq!(
+            first_n.check_hash(CAPACITY as u64),
+            Occurrence::FirstSuppressed
+        );
+    }
+
+    #[test]
+    fn notice_is_in_active_format() {
+        let record = || test::record(Level::Info, "app", None, format_args!("starting up"));
+        let msgs = log_msgs(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_logfmt()
+                .log_first_n(1),
+            &[record(), record(), record()],
+        );
+
+        assert_eq!(
+            msgs,
+            [
+                r#"level=info target=app msg="starting up""#,
+                r#"level=info target=app msg="suppressing further occurrences of: starting up""#,
+            ]
+        );
+    }
+}
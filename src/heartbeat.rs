@@ -0,0 +1,116 @@
+//! Periodic heartbeat messages.
+
+use super::*;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+
+/// Heartbeat settings, see [`Config::with_heartbeat()`].
+#[derive(Clone)]
+pub(crate) struct HeartbeatConfig {
+    pub(crate) interval: Duration,
+    pub(crate) level: Level,
+    pub(crate) message: String,
+}
+
+/// Background thread emitting the heartbeat, stopped when dropped.
+pub(crate) struct Heartbeat {
+    /// Dropped to stop the thread.
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    /// Starts the heartbeat configured in `config`, if any.
+    pub(crate) fn start(config: &Config) -> Option<Heartbeat> {
+        // Checked up front, as `logging_denied()` panics in debug builds.
+        if cfg!(feature = "deny-logging") {
+            return None;
+        }
+        let heartbeat = config.heartbeat.as_ref()?;
+        let interval = heartbeat.interval;
+        let level = config.level(heartbeat.level);
+        let message = heartbeat.message.clone();
+        let tag = config.internal_tag();
+        let buf_id = config.buf_id;
+        let destinations = config.destinations();
+
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::Builder::new()
+            .name("android_logger heartbeat".to_owned())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let mut writer = destinations.writer(buf_id, level, &tag);
+                    let _ = writer.write_str(&message);
+                    writer.flush();
+                }
+            })
+            .ok()?;
+
+        Some(Heartbeat {
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_all_emitted;
+
+    #[test]
+    fn heartbeat_is_emitted_until_flushed() {
+        let logger = AndroidLogger::new(Config::default().with_tag("heartbeat").with_heartbeat(
+            Duration::from_millis(5),
+            Level::Info,
+            "alive",
+        ));
+
+        let start = Instant::now();
+        let mut emitted = Vec::new();
+        while emitted.is_empty() && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(5));
+            emitted = take_all_emitted("heartbeat");
+        }
+        logger.flush();
+
+        assert!(!emitted.is_empty());
+        assert_eq!(emitted[0].priority, Level::Info);
+        assert_eq!(emitted[0].msg, "alive");
+
+        // The thread was stopped and joined by the flush.
+        take_all_emitted("heartbeat");
+        thread::sleep(Duration::from_millis(20));
+        assert!(take_all_emitted("heartbeat").is_empty());
+    }
+
+    #[test]
+    fn heartbeat_tag_is_truncated() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_tag("heartbeat-with-a-long-tag")
+                .with_display_tag_len(12)
+                .with_heartbeat(Duration::from_millis(5), Level::Info, "alive"),
+        );
+
+        let start = Instant::now();
+        let mut emitted = Vec::new();
+        while emitted.is_empty() && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(5));
+            emitted = take_all_emitted("heartbeat-..");
+        }
+        drop(logger);
+
+        assert!(!emitted.is_empty());
+        assert_eq!(emitted[0].msg, "alive");
+    }
+}
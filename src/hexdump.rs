@@ -0,0 +1,174 @@
+//! Hex dumps of raw bytes.
+
+use super::*;
+use std::fmt::Write;
+
+/// Number of bytes displayed on a single row.
+const BYTES_PER_ROW: usize = 16;
+
+/// Length of a full row, e.g.:
+///
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|
+/// ```
+const ROW_MAX_LEN: usize = 8 + 2 + BYTES_PER_ROW * 3 + 1 + 1 + BYTES_PER_ROW + 2;
+
+impl AndroidLogger {
+    /// Logs `bytes` as a classic offset/hex/ascii hex dump.
+    ///
+    /// Each row holds 16 bytes: the offset of the first byte as 8 hex digits,
+    /// the bytes themselves in hex (split into two groups of 8), and their
+    /// ascii representation between `|`s, where bytes outside the printable
+    /// ascii range are shown as `.`:
+    ///
+    /// ```text
+    /// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|
+    /// 00000010  2a                                                |*|
+    /// ```
+    ///
+    /// Rows are newline separated. Large dumps are split into several log
+    /// messages, always at row boundaries. The dump is filtered and routed to
+    /// a buffer like a record of `level` whose target is `tag`.
+    pub fn hexdump(&self, level: Level, tag: &str, bytes: &[u8]) {
+        let config = self.config();
+
+        let record = Record::builder()
+            .level(level)
+            .target(tag)
+            .args(format_args!(""))
+            .build();
+        if !self.enabled(record.metadata()) || !self.accepts(&record) {
+            return;
+        }
+        let buf_id = config.buffer_of(&record);
+
+        let mut tag_bytes: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
+        self.fill_tag_bytes(&mut tag_bytes, tag.as_bytes());
+        let tag: &CStr = unsafe { CStr::from_ptr(tag_bytes.as_ptr().cast()) };
+
+        let mut writer = config.writer(buf_id, config.level(level), tag);
+
+        for (row, chunk) in bytes.chunks(BYTES_PER_ROW).enumerate() {
+            // Emit what we have before a row would have to be split.
+            if writer.len + 1 + ROW_MAX_LEN > LOGGING_MSG_MAX_LEN {
+                writer.flush();
+            }
+            if writer.len > 0 {
+                let _ = writer.write_char('\n');
+            }
+            let _ = write_row(&mut writer, row * BYTES_PER_ROW, chunk);
+        }
+
+        writer.flush();
+    }
+}
+
+/// Writes a single row of the dump, without a trailing newline.
+fn write_row(f: &mut dyn fmt::Write, offset: usize, chunk: &[u8]) -> fmt::Result {
+    write!(f, "{:08x} ", offset)?;
+    for i in 0..BYTES_PER_ROW {
+        if i % 8 == 0 {
+            f.write_char(' ')?;
+        }
+        match chunk.get(i) {
+            Some(byte) => write!(f, "{:02x} ", byte)?,
+            None => f.write_str("   ")?,
+        }
+    }
+    f.write_str(" |")?;
+    for &byte in chunk {
+        let c = if byte.is_ascii_graphic() || byte == b' ' {
+            byte as char
+        } else {
+            '.'
+        };
+        f.write_char(c)?;
+    }
+    f.write_char('|')
+}
+
+/// Logs `bytes` as a hex dump through the global android logger.
+///
+/// See [`AndroidLogger::hexdump()`] for the output format.
+pub fn hexdump(level: Level, tag: &str, bytes: &[u8]) {
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .hexdump(level, tag, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn row_len_matches_full_row() {
+        let mut row = String::new();
+        write_row(&mut row, 0, &[0; BYTES_PER_ROW]).unwrap();
+
+        assert_eq!(row.len(), ROW_MAX_LEN);
+    }
+
+    #[test]
+    fn hexdump_small_buffer() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Debug));
+
+        logger.hexdump(Level::Debug, "dump", b"Hello, world!\n\0\xff*");
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].tag, "dump");
+        assert_eq!(
+            emitted[0].msg,
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|\n\
+             00000010  2a                                                |*|"
+        );
+    }
+
+    #[test]
+    fn hexdump_large_buffer_splits_at_rows() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Debug));
+        let bytes: Vec<u8> = (0..=u8::MAX).cycle().take(100 * BYTES_PER_ROW).collect();
+
+        logger.hexdump(Level::Debug, "dump", &bytes);
+
+        let emitted = take_emitted();
+        assert!(emitted.len() > 1);
+        let rows: Vec<&str> = emitted.iter().flat_map(|e| e.msg.split('\n')).collect();
+        assert_eq!(rows.len(), 100);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), ROW_MAX_LEN);
+            assert!(row.starts_with(&format!("{:08x}  ", i * BYTES_PER_ROW)));
+        }
+    }
+
+    #[test]
+    fn hexdump_respects_max_level() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        logger.hexdump(Level::Debug, "dump", b"bytes");
+
+        assert!(take_emitted().is_empty());
+    }
+
+    #[test]
+    fn hexdump_is_filtered_and_routed_like_records() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Debug)
+                .with_filter(FilterBuilder::new().parse("debug,app::quiet=info").build())
+                .block_targets(vec!["hyper".to_owned()])
+                .route_module_to_buffer("app::net", LogId::Radio),
+        );
+
+        for tag in ["app::net", "app::quiet", "hyper::client"] {
+            logger.hexdump(Level::Debug, tag, b"*");
+        }
+
+        let emitted: Vec<_> = take_emitted()
+            .into_iter()
+            .map(|e| (e.tag, e.buf_id))
+            .collect();
+        assert_eq!(emitted, [("app::net".to_owned(), Some(LogId::Radio))]);
+    }
+}
@@ -0,0 +1,192 @@
+//! Colored output to stderr (and stdout), for running on a development host.
+
+use super::*;
+use std::io;
+use std::iter;
+
+/// Writes each record as a colored, leveled line, e.g.
+/// `ERROR my_app::net: connection lost` with `ERROR` in red.
+pub(crate) struct HostLogger {
+    out: Mutex<Box<dyn io::Write + Send>>,
+    /// Where info, debug and trace records are written instead, if set.
+    verbose_out: Option<Mutex<Box<dyn io::Write + Send>>>,
+}
+
+impl HostLogger {
+    pub(crate) fn new(out: impl io::Write + Send + 'static) -> HostLogger {
+        HostLogger {
+            out: Mutex::new(Box::new(out)),
+            verbose_out: None,
+        }
+    }
+
+    /// Writes errors and warnings to `out`, and other records to
+    /// `verbose_out`.
+    pub(crate) fn by_level(
+        out: impl io::Write + Send + 'static,
+        verbose_out: impl io::Write + Send + 'static,
+    ) -> HostLogger {
+        HostLogger {
+            out: Mutex::new(Box::new(out)),
+            verbose_out: Some(Mutex::new(Box::new(verbose_out))),
+        }
+    }
+
+    fn out(&self, level: Level) -> &Mutex<Box<dyn io::Write + Send>> {
+        match &self.verbose_out {
+            Some(verbose_out) if level > Level::Warn => verbose_out,
+            _ => &self.out,
+        }
+    }
+}
+
+impl Config {
+    /// [`Config::with_dev_ergonomics()`], writing to `stderr` and `stdout`
+    /// instead of the process' ones, honoring
+    /// [`Config::host_stream_by_level()`].
+    pub(crate) fn with_dev_ergonomics_to(
+        self,
+        stderr: impl io::Write + Send + 'static,
+        stdout: impl io::Write + Send + 'static,
+    ) -> Config {
+        let logger = if self.host_stream_by_level {
+            HostLogger::by_level(stderr, stdout)
+        } else {
+            HostLogger::new(stderr)
+        };
+        let mut config = self.mirror_to(logger);
+        config.dev_ergonomics = true;
+        config
+    }
+}
+
+/// Returns the ANSI escape sequence setting the color of `level`.
+fn level_color(level: Level) -> &'static str {
+    match level {
+        Level::Error => "\x1b[31m",
+        Level::Warn => "\x1b[33m",
+        Level::Info => "\x1b[32m",
+        Level::Debug => "\x1b[34m",
+        Level::Trace => "\x1b[35m",
+    }
+}
+
+impl Log for HostLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let mut out = self
+            .out(record.level())
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let _ = writeln!(
+            out,
+            "{}{:<5}\x1b[0m {}: {}",
+            level_color(record.level()),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {
+        for out in iter::once(&self.out).chain(&self.verbose_out) {
+            let _ = out.lock().unwrap_or_else(PoisonError::into_inner).flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// A sink shared with the test.
+    #[derive(Clone, Default)]
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_are_colored_by_level() {
+        let sink = Sink::default();
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .mirror_to(HostLogger::new(sink.clone())),
+        );
+
+        for (level, msg) in [(Level::Error, "lost"), (Level::Info, "back")] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .target("app::net")
+                    .args(format_args!("{msg}"))
+                    .build(),
+            );
+        }
+
+        assert_eq!(
+            String::from_utf8(sink.0.lock().unwrap().clone()).unwrap(),
+            "\x1b[31mERROR\x1b[0m app::net: lost\n\x1b[32mINFO \x1b[0m app::net: back\n"
+        );
+    }
+
+    #[test]
+    fn records_are_split_by_level() {
+        let (stderr, stdout) = (Sink::default(), Sink::default());
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .mirror_to(HostLogger::by_level(stderr.clone(), stdout.clone())),
+        );
+
+        for (level, msg) in [(Level::Error, "lost"), (Level::Info, "back")] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .target("app::net")
+                    .args(format_args!("{msg}"))
+                    .build(),
+            );
+        }
+
+        let written = |sink: &Sink| String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(written(&stderr), "\x1b[31mERROR\x1b[0m app::net: lost\n");
+        assert_eq!(written(&stdout), "\x1b[32mINFO \x1b[0m app::net: back\n");
+    }
+
+    #[test]
+    fn dev_ergonomics_mirror_to_stderr() {
+        let (stderr, stdout) = (Sink::default(), Sink::default());
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_dev_ergonomics_to(stderr.clone(), stdout.clone()),
+        );
+
+        logger.log(&test::record(
+            Level::Error,
+            "my_app::net",
+            None,
+            format_args!("connection lost"),
+        ));
+
+        let written = |sink: &Sink| String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            written(&stderr),
+            "\x1b[31mERROR\x1b[0m my_app::net: connection lost\n"
+        );
+        assert_eq!(written(&stdout), "");
+    }
+}
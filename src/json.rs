@@ -0,0 +1,374 @@
+//! JSON output format.
+
+use super::*;
+
+/// Writes `record` as a single JSON object, e.g.:
+///
+/// ```text
+/// {"level":"INFO","target":"my_app::net","msg":"connected to \"host\""}
+/// ```
+///
+/// The base key-values of `config` are appended as further string members,
+/// followed by the record's own key-values (with the `kv` feature), and by
+/// a `"repeat"` number member for the repeats of [`Config::defeat_chatty()`].
+/// Key-values named after one of the [`FIELDS`] are prefixed with `kv_`, so
+/// that the object never holds duplicate members.
+pub(crate) fn write_record(
+    f: &mut dyn fmt::Write,
+    record: &Record,
+    config: &Config,
+    repeat: Option<u64>,
+) -> fmt::Result {
+    f.write_char('{')?;
+    if let Some(version) = config.schema_version {
+        write!(f, "\"v\":{version},")?;
+    }
+    f.write_str("\"level\":\"")?;
+    f.write_str(record.level().as_str())?;
+    f.write_str("\",\"target\":")?;
+    write_str(f, record.target())?;
+    f.write_str(",\"msg\":")?;
+    write_display(f, record.args())?;
+    for (key, value) in config.base_kv() {
+        #[cfg(feature = "kv")]
+        if record.key_values().get(key.into()).is_some() && config.emits_kv(key) {
+            continue;
+        }
+        f.write_char(',')?;
+        write_key(f, key)?;
+        f.write_char(':')?;
+        write_str(f, value)?;
+    }
+    #[cfg(feature = "kv")]
+    {
+        let mut visitor = KvVisitor { f, config };
+        record
+            .key_values()
+            .visit(&mut visitor)
+            .map_err(|_| fmt::Error)?;
+    }
+    if let Some(repeat) = repeat {
+        write!(f, ",\"repeat\":{repeat}")?;
+    }
+    f.write_char('}')
+}
+
+/// Names of the fields written for records, besides their key-values.
+pub(crate) const FIELDS: [&str; 5] = ["v", "level", "target", "msg", "repeat"];
+
+/// Writes the key-value `key` as a quoted JSON string, prefixed with `kv_` if
+/// it's one of the [`FIELDS`].
+fn write_key(f: &mut dyn fmt::Write, key: &str) -> fmt::Result {
+    f.write_char('"')?;
+    if FIELDS.contains(&key) {
+        f.write_str("kv_")?;
+    }
+    JsonEscaper(f).write_str(key)?;
+    f.write_char('"')
+}
+
+/// Writes `s` as a quoted JSON string.
+fn write_str(f: &mut dyn fmt::Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    JsonEscaper(f).write_str(s)?;
+    f.write_char('"')
+}
+
+/// Writes the [`Display`] output of `value` as a quoted JSON string.
+///
+/// [`Display`]: fmt::Display
+fn write_display(f: &mut dyn fmt::Write, value: &dyn fmt::Display) -> fmt::Result {
+    f.write_char('"')?;
+    write!(JsonEscaper(f), "{}", value)?;
+    f.write_char('"')
+}
+
+/// Escapes everything written through it for use inside a JSON string.
+///
+/// Escaping happens while writing, so no intermediate buffer is needed.
+pub(crate) struct JsonEscaper<'a>(pub(crate) &'a mut dyn fmt::Write);
+
+impl fmt::Write for JsonEscaper<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut plain_from = 0;
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '"' => Some("\\\""),
+                '\\' => Some("\\\\"),
+                '\n' => Some("\\n"),
+                '\r' => Some("\\r"),
+                '\t' => Some("\\t"),
+                '\0'..='\u{1f}' => None,
+                _ => continue,
+            };
+            self.0.write_str(&s[plain_from..i])?;
+            match escaped {
+                Some(escaped) => self.0.write_str(escaped)?,
+                None => write!(self.0, "\\u{:04x}", c as u32)?,
+            }
+            plain_from = i + c.len_utf8();
+        }
+        self.0.write_str(&s[plain_from..])
+    }
+}
+
+#[cfg(feature = "kv")]
+struct KvVisitor<'a> {
+    f: &'a mut dyn fmt::Write,
+    config: &'a Config,
+}
+
+#[cfg(feature = "kv")]
+impl<'kvs> log::kv::VisitSource<'kvs> for KvVisitor<'_> {
+    fn visit_pair(
+        &mut self,
+        key: log::kv::Key<'kvs>,
+        value: log::kv::Value<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        if !self.config.emits_kv(key.as_str()) {
+            return Ok(());
+        }
+        self.f.write_char(',')?;
+        write_key(self.f, key.as_str())?;
+        self.f.write_char(':')?;
+        write_display(self.f, &value)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn escaped(s: &str) -> String {
+        let mut out = String::new();
+        JsonEscaper(&mut out).write_str(s).unwrap();
+        out
+    }
+
+    #[test]
+    fn escapes_special_chars() {
+        assert_eq!(escaped("plain"), "plain");
+        assert_eq!(escaped("say \"hi\""), "say \\\"hi\\\"");
+        assert_eq!(escaped("C:\\dir"), "C:\\\\dir");
+        assert_eq!(escaped("a\nb\r\tc"), "a\\nb\\r\\tc");
+        assert_eq!(escaped("\x01\x1f"), "\\u0001\\u001f");
+        assert_eq!(escaped("héllo ✓"), "héllo ✓");
+    }
+
+    #[test]
+    fn json_formatted_record() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_json(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Warn)
+                .target("app::net")
+                .args(format_args!("\"quoted\" C:\\path\nnext\x01"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"WARN","target":"app::net","msg":"\"quoted\" C:\\path\nnext\u0001"}"#
+        );
+    }
+
+    #[test]
+    fn json_includes_base_key_values() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_base_kv(vec![("app".to_owned(), "1.2.3".to_owned())])
+                .format_json(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"app","msg":"msg","app":"1.2.3"}"#
+        );
+    }
+
+    #[test]
+    fn json_includes_schema_version() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_schema_version(2)
+                .format_json(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"v":2,"level":"INFO","target":"app","msg":"msg"}"#
+        );
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn record_key_values_override_base_key_values() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_base_kv(vec![
+                    ("app".to_owned(), "1.2.3".to_owned()),
+                    ("device".to_owned(), "pixel".to_owned()),
+                ])
+                .format_json(),
+        );
+        let kvs = [("device", log::kv::Value::from("emulator"))];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"app","msg":"msg","app":"1.2.3","device":"emulator"}"#
+        );
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn json_only_includes_allowed_key_values() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .kv_allowlist(vec!["user".to_owned(), "token".to_owned(), "n".to_owned()])
+                .kv_blocklist(vec!["token".to_owned()])
+                .format_json(),
+        );
+        let kvs = [
+            ("user", log::kv::Value::from("alice")),
+            ("token", "secret".into()),
+            ("trace", "noisy".into()),
+            ("n", 3.into()),
+        ];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"app","msg":"msg","user":"alice","n":"3"}"#
+        );
+    }
+
+    #[test]
+    fn base_key_values_do_not_collide_with_fields() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_base_kv(vec![("level".to_owned(), "high".to_owned())])
+                .format_json(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"app","msg":"msg","kv_level":"high"}"#
+        );
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn key_values_do_not_collide_with_fields() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_json(),
+        );
+        let kvs = [
+            ("msg", log::kv::Value::from("other")),
+            ("repeat", 2.into()),
+            ("user", "alice".into()),
+        ];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"app","msg":"msg","kv_msg":"other","kv_repeat":"2","user":"alice"}"#
+        );
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn json_includes_key_values() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_json(),
+        );
+        let kvs = [("user", log::kv::Value::from("a\"b")), ("n", 3.into())];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"app","msg":"msg","user":"a\"b","n":"3"}"#
+        );
+    }
+}
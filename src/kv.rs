@@ -0,0 +1,196 @@
+//! Handling of a record's key-values.
+
+use super::*;
+use log::kv::Value;
+
+/// Key overriding the buffer a record is logged to.
+pub(crate) const BUFFER_KEY: &str = "android_buffer";
+
+/// Returns the buffer named by the [`BUFFER_KEY`] of `record`, if any.
+pub(crate) fn buffer_override(record: &Record) -> Option<LogId> {
+    let value = record.key_values().get(BUFFER_KEY.into())?;
+    parse_value(&value)
+}
+
+/// Writes the key-values of `record` on separate, indented lines, each
+/// preceded by a newline.
+pub(crate) fn write_pretty(
+    f: &mut dyn fmt::Write,
+    record: &Record,
+    config: &Config,
+) -> fmt::Result {
+    struct PrettyVisitor<'a> {
+        f: &'a mut dyn fmt::Write,
+        config: &'a Config,
+    }
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for PrettyVisitor<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if self.config.emits_kv(key.as_str()) {
+                write!(self.f, "\n    {key}: {value}")?;
+            }
+            Ok(())
+        }
+    }
+
+    record
+        .key_values()
+        .visit(&mut PrettyVisitor { f, config })
+        .map_err(|_| fmt::Error)
+}
+
+fn parse_value<T: FromStr>(value: &Value) -> Option<T> {
+    match value.to_borrowed_str() {
+        Some(s) => s.parse().ok(),
+        None => value.to_string().parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{log_emitted, take_emitted};
+
+    fn log_with_buffer(logger: &AndroidLogger, buffer: Value) {
+        let kvs = [(BUFFER_KEY, buffer)];
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("msg"))
+                .key_values(&kvs)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn buffer_key_overrides_configured_buffer() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_log_buffer(LogId::System),
+        );
+
+        log_with_buffer(&logger, Value::from("radio"));
+        log_with_buffer(&logger, Value::from_display(&"crash"));
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("msg"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 3);
+        assert_eq!(emitted[0].buf_id, Some(LogId::Radio));
+        assert_eq!(emitted[1].buf_id, Some(LogId::Crash));
+        assert_eq!(emitted[2].buf_id, Some(LogId::System));
+    }
+
+    #[test]
+    fn pretty_kv_are_indented_lines() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .pretty_kv(true),
+        );
+        let kvs: [(&str, Value); 2] = [("user", Value::from("alice")), ("attempt", Value::from(3))];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("login failed"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            emitted[0].msg,
+            "login failed\n    user: alice\n    attempt: 3"
+        );
+    }
+
+    #[test]
+    fn blocked_pretty_kv_are_dropped() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .kv_blocklist(vec!["password".to_owned()])
+                .pretty_kv(true),
+        );
+        let kvs: [(&str, Value); 2] = [
+            ("user", Value::from("alice")),
+            ("password", "hunter2".into()),
+        ];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("login failed"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted[0].msg, "login failed\n    user: alice");
+    }
+
+    #[test]
+    fn invalid_buffer_key_falls_back_to_configured_buffer() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_log_buffer(LogId::System),
+        );
+
+        log_with_buffer(&logger, Value::from("nope"));
+        log_with_buffer(&logger, Value::from(42));
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 2);
+        assert!(emitted.iter().all(|e| e.buf_id == Some(LogId::System)));
+    }
+
+    #[test]
+    fn buffer_key_is_not_written_out() {
+        let log = |config: Config| {
+            let kvs: [(&str, Value); 2] = [(BUFFER_KEY, "crash".into()), ("user", "alice".into())];
+            log_emitted(
+                config.with_max_level(LevelFilter::Info),
+                &[Record::builder()
+                    .level(Level::Info)
+                    .target("app")
+                    .args(format_args!("msg"))
+                    .key_values(&kvs)
+                    .build()],
+            )
+            .into_iter()
+            .map(|e| (e.buf_id, e.msg))
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            log(Config::default().format_json()),
+            [(
+                Some(LogId::Crash),
+                r#"{"level":"INFO","target":"app","msg":"msg","user":"alice"}"#.to_owned()
+            )]
+        );
+        assert_eq!(
+            log(Config::default().format_logfmt()),
+            [(
+                Some(LogId::Crash),
+                "level=info target=app msg=\"msg\" user=alice".to_owned()
+            )]
+        );
+        assert_eq!(
+            log(Config::default().pretty_kv(true)),
+            [(Some(LogId::Crash), "msg\n    user: alice".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Named sets of records, for routing them.
+
+use super::*;
+
+/// Predicate of a label, see [`Config::with_label()`].
+pub(crate) type LabelFn = Arc<dyn Fn(&Record) -> bool + Sync + Send>;
+
+impl Config {
+    /// Whether `record` has the label `name`, `false` if there is no such
+    /// label.
+    fn has_label(&self, name: &str, record: &Record) -> bool {
+        self.labels
+            .iter()
+            .find(|(label, _)| label == name)
+            .is_some_and(|(_, matches)| matches(record))
+    }
+
+    /// Returns the buffer `record` is routed to by its labels, if any.
+    pub(crate) fn label_buffer(&self, record: &Record) -> Option<LogId> {
+        self.label_buffers
+            .iter()
+            .find(|(label, _)| self.has_label(label, record))
+            .map(|&(_, buf_id)| buf_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::log_emitted;
+
+    #[test]
+    fn labelled_records_are_routed() {
+        let record = |target| test::record(Level::Info, target, None, format_args!("msg"));
+        let emitted = log_emitted(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_label("network", |record| {
+                    ["hyper", "my_app::http"]
+                        .iter()
+                        .any(|module| is_in_module(record.target(), module))
+                })
+                .route_label_to_buffer("network", LogId::Radio)
+                .route_label_to_buffer("missing", LogId::Crash)
+                .route_module_to_buffer("my_app", LogId::System),
+            &["hyper::client", "my_app::http", "my_app::ui", "other"].map(record),
+        );
+
+        let buf_ids: Vec<_> = emitted.into_iter().map(|e| e.buf_id).collect();
+        assert_eq!(
+            buf_ids,
+            [
+                Some(LogId::Radio),
+                Some(LogId::Radio),
+                Some(LogId::System),
+                None
+            ]
+        );
+    }
+}
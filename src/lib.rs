@@ -62,6 +62,35 @@
 //!         .format(|f, record| write!(f, "my_app: {}", record.args()))
 //! )
 //! ```
+//!
+//! ## Enforcing no logging
+//!
+//! Builds that must provably not emit any logs (e.g. to rule out leaking
+//! personal data) can enable the `deny-logging` feature. With it, nothing
+//! reaches logcat: any attempt to emit a log panics in debug builds, and is
+//! silently dropped otherwise. Running the test suite of such a build in
+//! debug mode in CI therefore catches stray log statements.
+//!
+//! ## Kernel buffer
+//!
+//! Privileged processes (e.g. system services running as `root` or with the
+//! `CAP_SYSLOG` capability) may log to the [`LogId::Kernel`] buffer. With the
+//! `kmsg` feature enabled, records logged there start with the syslog
+//! severity of their level (e.g. `<3>` for `Error`), as `dmesg` expects for
+//! `/dev/kmsg`. Records logged to other buffers are not affected. Regular
+//! apps can't write to this buffer at all.
+//!
+//! ## Reserved keys
+//!
+//! With the `kv` feature enabled, some keys of a record's key-values change
+//! how the record is logged:
+//!
+//! - `android_buffer`: name of the [`LogId`] buffer to log the record to
+//!   (e.g. `info!(android_buffer = "crash"; "..")`), overriding the one set
+//!   with [`Config::with_log_buffer()`]. Unknown names are ignored.
+//!
+//! Reserved keys are not written out with the other key-values (e.g. by
+//! [`Config::format_json()`]).
 
 #[cfg(target_os = "android")]
 extern crate android_log_sys as log_ffi;
@@ -69,15 +98,115 @@ extern crate android_log_sys as log_ffi;
 use log::{Level, LevelFilter, Log, Metadata, Record};
 #[cfg(target_os = "android")]
 use log_ffi::LogPriority;
+#[cfg(target_os = "android")]
+use priority::{closest_level, level_to_priority};
+use std::borrow::Cow;
 use std::ffi::{CStr, CString};
-use std::fmt;
+use std::fmt::{self, Write as _};
 use std::mem::{self, MaybeUninit};
 use std::ptr;
-use std::sync::OnceLock;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use chatty::Chatty;
+use collisions::TagCollisions;
+use dedup::{Dedup, DedupWindow};
+use delta::Deltas;
+use directives::Directives;
+use first_n::{FirstN, Occurrence};
+use heartbeat::{Heartbeat, HeartbeatConfig};
+use labels::LabelFn;
+use newlines::NewlineWriter;
+use presets::ActivePreset;
+use rate_limit::TagRateLimit;
+#[cfg(unix)]
+use socket::SocketTee;
+use state::State;
+use suppressions::Suppressions;
+use tag_counter::TagCounters;
+
+mod alog;
+mod backend;
+mod binary;
+mod buffer_scope;
+mod capture;
+mod chatty;
+mod clock;
+mod collisions;
+mod correlation;
+mod dedup;
+mod delta;
+mod directives;
+mod early;
+mod empty_message;
+mod explain;
+mod first_n;
+mod heartbeat;
+mod hexdump;
+#[cfg(not(target_os = "android"))]
+mod host;
+mod json;
+#[cfg(feature = "kv")]
+mod kv;
+mod labels;
+mod lifecycle;
+mod log_if;
+#[cfg(feature = "kv")]
+mod log_struct;
+mod logfmt;
+mod newlines;
+mod panic;
+mod presets;
+#[cfg(target_os = "android")]
+pub mod priority;
+mod profile;
+#[cfg(any(target_os = "android", test))]
+mod properties;
+mod rate_limit;
+mod screen;
+mod session;
+mod shutdown;
+#[cfg(unix)]
+mod socket;
+mod split_preview;
+mod state;
+mod suppressions;
+mod tag_counter;
+pub mod test;
+mod timed;
+mod tooling_header;
+mod validate;
 
+#[doc(hidden)]
+pub use alog::__alog;
+pub use backend::LogBackend;
+pub use binary::BinaryEncoding;
+pub use buffer_scope::{buffer_scope, BufferScope};
+pub use capture::{assert_no_logs, test_capture, CapturedRecord};
+pub use clock::{log_with_time, Clock, SystemClock};
+pub use correlation::set_correlation_id;
+pub use delta::log_delta;
+pub use early::enable_early_buffering;
+pub use empty_message::EmptyMessage;
 pub use env_filter::{Builder as FilterBuilder, Filter};
+pub use hexdump::hexdump;
+pub use log_if::log_if;
+#[cfg(feature = "kv")]
+pub use log_struct::{log_struct, AsLogFields};
+pub use newlines::NewlineHandling;
+pub use panic::install_panic_hook;
+pub use presets::set_active_format;
+#[cfg(target_os = "android")]
+pub use properties::poll_system_property;
+pub use screen::set_current_screen;
+pub use shutdown::shutdown;
+pub use timed::{timed, Timed};
+pub use validate::ConfigError;
 
-pub(crate) type FormatFn = Box<dyn Fn(&mut dyn fmt::Write, &Record) -> fmt::Result + Sync + Send>;
+pub(crate) type FormatFn = Arc<dyn Fn(&mut dyn fmt::Write, &Record) -> fmt::Result + Sync + Send>;
+pub(crate) type FilteredFn = Arc<dyn Fn(&Record) -> bool + Sync + Send>;
 
 /// Possible identifiers of a specific buffer of Android logging system for
 /// logging a message.
@@ -110,6 +239,104 @@ pub enum LogId {
     Stats,
 }
 
+impl FromStr for LogId {
+    type Err = ParseLogIdError;
+
+    /// Parses the name of a buffer, as used by `logcat -b`
+    /// (e.g. `main` or `crash`), case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const NAMES: [(&str, LogId); 8] = [
+            ("main", LogId::Main),
+            ("radio", LogId::Radio),
+            ("events", LogId::Events),
+            ("system", LogId::System),
+            ("crash", LogId::Crash),
+            ("kernel", LogId::Kernel),
+            ("security", LogId::Security),
+            ("stats", LogId::Stats),
+        ];
+
+        NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|&(_, log_id)| log_id)
+            .ok_or(ParseLogIdError(()))
+    }
+}
+
+/// Error returned when parsing a [`LogId`] from an unknown buffer name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseLogIdError(());
+
+impl fmt::Display for ParseLogIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown Android log buffer name")
+    }
+}
+
+impl std::error::Error for ParseLogIdError {}
+
+/// Returns the buffers this process can write logs to.
+///
+/// Most buffers are only writable by privileged processes, and writes to the
+/// others fail silently. On Android, this probes the text buffers ([`Main`],
+/// [`Radio`], [`System`] and [`Crash`]) by writing a throwaway `VERBOSE` line
+/// to each of them (under the `android_logger` tag), and returns those where
+/// the write succeeded. The binary ([`Events`], [`Security`], [`Stats`]) and
+/// read-only ([`Kernel`]) buffers are never returned.
+///
+/// Note, that liblog may accept a write which is later dropped by logd, so
+/// this is a best-effort check only. On other platforms, all buffers are
+/// returned.
+///
+/// [`Main`]: LogId::Main
+/// [`Radio`]: LogId::Radio
+/// [`System`]: LogId::System
+/// [`Crash`]: LogId::Crash
+/// [`Events`]: LogId::Events
+/// [`Security`]: LogId::Security
+/// [`Stats`]: LogId::Stats
+/// [`Kernel`]: LogId::Kernel
+pub fn accessible_buffers() -> Vec<LogId> {
+    #[cfg(target_os = "android")]
+    {
+        if logging_denied() {
+            return Vec::new();
+        }
+
+        [LogId::Main, LogId::Radio, LogId::System, LogId::Crash]
+            .into_iter()
+            .filter(|&log_id| {
+                let Some(buf_id) = LogId::to_native(Some(log_id)) else {
+                    return false;
+                };
+                let written = unsafe {
+                    log_ffi::__android_log_buf_write(
+                        buf_id as log_ffi::c_int,
+                        LogPriority::VERBOSE as log_ffi::c_int,
+                        INTERNAL_TAG.as_ptr(),
+                        c"probing log buffer access".as_ptr(),
+                    )
+                };
+                written >= 0
+            })
+            .collect()
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        vec![
+            LogId::Main,
+            LogId::Radio,
+            LogId::Events,
+            LogId::System,
+            LogId::Crash,
+            LogId::Kernel,
+            LogId::Security,
+            LogId::Stats,
+        ]
+    }
+}
+
 #[cfg(target_os = "android")]
 impl LogId {
     const fn to_native(log_id: Option<Self>) -> Option<log_ffi::log_id_t> {
@@ -125,6 +352,19 @@ impl LogId {
             None => None,
         }
     }
+
+    const fn from_native(log_id: log_ffi::log_id_t) -> Self {
+        match log_id {
+            log_ffi::log_id_t::RADIO => Self::Radio,
+            log_ffi::log_id_t::EVENTS => Self::Events,
+            log_ffi::log_id_t::SYSTEM => Self::System,
+            log_ffi::log_id_t::CRASH => Self::Crash,
+            log_ffi::log_id_t::KERNEL => Self::Kernel,
+            log_ffi::log_id_t::SECURITY => Self::Security,
+            log_ffi::log_id_t::STATS => Self::Stats,
+            _ => Self::Main,
+        }
+    }
 }
 
 /// Outputs log to Android system.
@@ -135,6 +375,22 @@ fn android_log(
     tag: &CStr,
     msg: &CStr,
 ) {
+    if logging_denied() {
+        return;
+    }
+
+    if capture_record(
+        buf_id.map(LogId::from_native),
+        closest_level(prio),
+        tag,
+        msg,
+    ) {
+        return;
+    }
+
+    #[cfg(test)]
+    tests::capture_native(buf_id, prio, tag, msg);
+
     if let Some(buf_id) = buf_id {
         unsafe {
             log_ffi::__android_log_buf_write(
@@ -156,127 +412,601 @@ fn android_log(
 }
 
 /// Dummy output placeholder for tests.
-#[cfg(not(target_os = "android"))]
-fn android_log(_buf_id: Option<LogId>, _priority: Level, _tag: &CStr, _msg: &CStr) {}
+#[cfg(all(not(target_os = "android"), not(test)))]
+fn android_log(buf_id: Option<LogId>, priority: Level, tag: &CStr, msg: &CStr) {
+    if logging_denied() {
+        return;
+    }
+    capture_record(buf_id, priority, tag, msg);
+}
+
+/// Records the output, so unit tests can inspect what would be sent to Android.
+#[cfg(all(not(target_os = "android"), test))]
+fn android_log(buf_id: Option<LogId>, priority: Level, tag: &CStr, msg: &CStr) {
+    if logging_denied() {
+        return;
+    }
+    if capture_record(buf_id, priority, tag, msg) {
+        return;
+    }
+    tests::capture(buf_id, priority, tag, msg);
+}
+
+/// Hands the output to the active [`test_capture()`], if any.
+///
+/// Returns whether it was captured.
+fn capture_record(buf_id: Option<LogId>, level: Level, tag: &CStr, msg: &CStr) -> bool {
+    capture::try_capture(|| CapturedRecord {
+        buf_id,
+        level,
+        tag: tag.to_string_lossy().into_owned(),
+        msg: msg.to_string_lossy().into_owned(),
+    })
+}
+
+/// Checks whether this build must not emit any logs, because of the
+/// `deny-logging` feature.
+///
+/// In debug builds, emitting a log then panics, so any stray log statement
+/// is caught by tests. Otherwise, the log must be silently dropped.
+#[inline(always)]
+fn logging_denied() -> bool {
+    if cfg!(feature = "deny-logging") {
+        debug_assert!(false, "logging is disabled in this build");
+        return true;
+    }
+    false
+}
 
 /// Underlying android logger backend
 pub struct AndroidLogger {
     config: OnceLock<Config>,
+    filter_override: RwLock<Option<Filter>>,
+    /// Stops the heartbeat thread, if any, when dropped.
+    heartbeat: Mutex<Option<Heartbeat>>,
+    /// Whether [`AndroidLogger::shutdown()`] was called.
+    shut_down: AtomicBool,
+    /// Previous values of [`AndroidLogger::log_delta()`].
+    deltas: Deltas,
+    /// Set by [`AndroidLogger::set_active_format()`].
+    active_preset: RwLock<ActivePreset>,
+    /// Whether a drop was explained, see [`Config::explain_drops()`].
+    drop_explained: AtomicBool,
 }
 
 impl AndroidLogger {
     /// Create new logger instance from config
-    pub fn new(config: Config) -> AndroidLogger {
+    pub fn new(mut config: Config) -> AndroidLogger {
+        if config.session_id {
+            config.session = Some(session::generate());
+        }
         AndroidLogger {
+            heartbeat: Mutex::new(Heartbeat::start(&config)),
             config: OnceLock::from(config),
+            filter_override: RwLock::new(None),
+            shut_down: AtomicBool::new(false),
+            deltas: Deltas::default(),
+            active_preset: RwLock::default(),
+            drop_explained: AtomicBool::new(false),
         }
     }
 
     fn config(&self) -> &Config {
         self.config.get_or_init(Config::default)
     }
+
+    /// Replaces the filter of this logger at runtime.
+    ///
+    /// The new filter takes precedence over both the filter and the maximum
+    /// level of the [`Config`] this logger was created with. Note, that
+    /// records are still pre-filtered by [`log::max_level()`], so it may need
+    /// to be raised as well.
+    pub fn set_filter(&self, filter: Filter) {
+        *self
+            .filter_override
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(filter);
+    }
+
+    fn filter_matches(&self, record: &Record) -> bool {
+        match &*self
+            .filter_override
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            Some(filter) => filter.matches(record),
+            None => self.config().filter_matches(record),
+        }
+    }
 }
 
 static ANDROID_LOGGER: OnceLock<AndroidLogger> = OnceLock::new();
 
+/// Held for the whole emission of a record when [`Config::serialize_output()`]
+/// is enabled.
+static OUTPUT_LOCK: Mutex<()> = Mutex::new(());
+
 const LOGGING_TAG_MAX_LEN: usize = 23;
 const LOGGING_MSG_MAX_LEN: usize = 4000;
 
 impl Default for AndroidLogger {
     /// Create a new logger with default config
     fn default() -> AndroidLogger {
-        AndroidLogger {
-            config: OnceLock::from(Config::default()),
-        }
+        AndroidLogger::new(Config::default())
     }
 }
 
 impl Log for AndroidLogger {
     fn enabled(&self, metadata: &Metadata) -> bool {
+        if self.shut_down.load(Ordering::Relaxed) {
+            return false;
+        }
+
         let config = self.config();
+        let max_level = match &*self
+            .filter_override
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            Some(filter) => filter.filter(),
+            None => config.log_level.unwrap_or_else(log::max_level),
+        };
         // todo: consider __android_log_is_loggable.
-        metadata.level() <= config.log_level.unwrap_or_else(log::max_level)
+        metadata.level() <= max_level
     }
 
     fn log(&self, record: &Record) {
+        self.emit(record, None, None)
+    }
+
+    fn flush(&self) {
+        drop(
+            self.heartbeat
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take(),
+        );
+        self.log_suppression_summary();
+        if let Some(mirror) = &self.config().mirror {
+            mirror.flush();
+        }
+    }
+}
+
+impl AndroidLogger {
+    /// Checks whether `record`, once enabled, passes the other checks of the
+    /// records logged: the priority floor, the blocked targets and the filter.
+    fn accepts(&self, record: &Record) -> bool {
         let config = self.config();
 
-        if !self.enabled(record.metadata()) {
-            return;
+        if config
+            .min_priority
+            .is_some_and(|min| priority_value(config.level(record.level())) < min)
+        {
+            return false;
+        }
+
+        if config.is_blocked(record.target()) {
+            return false;
         }
 
         // this also checks the level, but only if a filter was
         // installed.
-        if !config.filter_matches(record) {
+        self.filter_matches(record) || config.resurrect_filtered(record)
+    }
+
+    /// Writes `notice`, a message of the logger about `record` (e.g. that its
+    /// repeats are suppressed), in the active format, as if it were the
+    /// message of `record`.
+    fn write_notice(
+        &self,
+        writer: &mut PlatformLogWriter,
+        record: &Record,
+        notice: fmt::Arguments,
+    ) {
+        let config = self.config();
+        let notice = Record::builder()
+            .level(record.level())
+            .target(record.target())
+            .module_path(record.module_path())
+            .args(notice)
+            .build();
+        let _ = match self.active_format() {
+            (Some(format), _) => format(writer, &notice),
+            (_, OutputFormat::Json) => json::write_record(writer, &notice, config, None),
+            (_, OutputFormat::Logfmt) => logfmt::write_record(writer, &notice, config, None),
+            _ => writer.write_fmt(*notice.args()),
+        };
+        writer.flush();
+    }
+
+    /// Logs `record`, under `tag` instead of the configured one, and with the
+    /// timestamp `time` instead of the current time, if given.
+    fn emit(&self, record: &Record, tag: Option<&str>, time: Option<SystemTime>) {
+        let config = self.config();
+
+        if !self.enabled(record.metadata()) {
+            self.explain_drop(record);
             return;
         }
 
+        if self.handle_empty_message(record, tag, time) {
+            return;
+        }
+
+        if !self.accepts(record) {
+            return;
+        }
+
+        if let Some(mirror) = config.mirror.as_ref().filter(|_| !logging_denied()) {
+            mirror.log(record);
+        }
+
         // tag must not exceed LOGGING_TAG_MAX_LEN
         let mut tag_bytes: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
 
-        let module_path = record.module_path().unwrap_or_default().to_owned();
+        let module_path = record.module_path().unwrap_or_default();
 
         // If no tag was specified, use module name
+        let module_tag = config.module_tag(module_path);
+        let subsystem_tag = config.subsystem_tag();
         let custom_tag = &config.tag;
-        let tag = custom_tag
-            .as_ref()
-            .map(|s| s.as_bytes())
-            .unwrap_or_else(|| module_path.as_bytes());
+        let level_tag = config.level_tag(record.level());
+        let has_custom_tag = custom_tag.is_some() || subsystem_tag.is_some() || level_tag.is_some();
+        let prefix_module_path = (tag.is_none() && has_custom_tag && !config.without_tag)
+            || (config.always_include_module_path && !module_path.is_empty());
+        let tag_from_module = tag.is_none() && !has_custom_tag && !config.without_tag;
+        let tag = tag
+            .or(level_tag)
+            .map(str::as_bytes)
+            .or_else(|| config.without_tag.then_some(&b""[..]))
+            .or_else(|| subsystem_tag.as_deref().map(str::as_bytes))
+            .or_else(|| custom_tag.as_ref().map(|s| s.as_bytes()))
+            .unwrap_or_else(|| module_tag.as_bytes());
 
         // truncate the tag here to fit into LOGGING_TAG_MAX_LEN
         self.fill_tag_bytes(&mut tag_bytes, tag);
         // use stack array as C string
-        let tag: &CStr = unsafe { CStr::from_ptr(mem::transmute(tag_bytes.as_ptr())) };
+        let tag: &CStr = unsafe { CStr::from_ptr(tag_bytes.as_ptr().cast()) };
+
+        if let Some(collisions) = config.tag_collisions.as_ref().filter(|_| tag_from_module) {
+            if let Some(other) = collisions.check(tag.to_bytes(), module_path) {
+                if !config.silence_internal {
+                    write_internal(
+                        Level::Warn,
+                        format_args!(
+                            "tag `{}` is shared by modules `{other}` and `{module_path}`",
+                            tag.to_string_lossy()
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(rate_limit) = &config.tag_rate_limit {
+            if !rate_limit.check(tag.to_bytes(), config.clock().now()) {
+                if let Some(suppressions) = &config.suppressions {
+                    suppressions.rate_limited.fetch_add(1, Ordering::Relaxed);
+                }
+                return;
+            }
+        }
 
         // message must not exceed LOGGING_MSG_MAX_LEN
         // therefore split log message into multiple log calls
-        let mut writer = PlatformLogWriter::new(config.buf_id, record.level(), tag);
+        let buf_id = config.buffer_of(record);
+
+        let _serialized = config
+            .serialize_output
+            .then(|| OUTPUT_LOCK.lock().unwrap_or_else(PoisonError::into_inner));
+
+        if config.log_thread_lifecycle {
+            lifecycle::register(config, buf_id, config.level(Level::Info), tag);
+        }
+
+        let mut writer = config.writer(buf_id, config.level(record.level()), tag);
+        writer.max_chunks = config.max_chunks.unwrap_or(usize::MAX);
+        writer.skip_whitespace_only_chunks = config.skip_whitespace_only_chunks;
+        writer.trim_trailing_newline = config.trim_trailing_newline;
+        writer.max_len = config.message_len_for_tag(tag);
+        writer.max_bytes = config.format_memory_cap.unwrap_or(usize::MAX);
+        if config.no_split {
+            writer.max_chunks = 1;
+            writer.truncation_marker = &[];
+        }
+
+        if let Some(dedup) = &config.dedup {
+            match dedup.check(record, config.clock().now()) {
+                Dedup::Suppressed => {
+                    if let Some(suppressions) = &config.suppressions {
+                        suppressions.deduped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return;
+                }
+                Dedup::Emit {
+                    suppressed: Some(suppressed),
+                } => {
+                    self.write_notice(
+                        &mut writer,
+                        record,
+                        format_args!("suppressed {suppressed} duplicate messages"),
+                    );
+                }
+                Dedup::Emit { suppressed: None } => {}
+            }
+        }
+
+        if let Some(first_n) = &config.first_n {
+            match first_n.check(record) {
+                Occurrence::Emit => {}
+                Occurrence::FirstSuppressed => {
+                    self.write_notice(
+                        &mut writer,
+                        record,
+                        format_args!("suppressing further occurrences of: {}", record.args()),
+                    );
+                    if let Some(suppressions) = &config.suppressions {
+                        suppressions.over_first_n.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return;
+                }
+                Occurrence::Suppressed => {
+                    if let Some(suppressions) = &config.suppressions {
+                        suppressions.over_first_n.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return;
+                }
+            }
+        }
+
+        let repeat = config
+            .chatty
+            .as_ref()
+            .and_then(|chatty| chatty.repeat(record));
+
+        #[cfg(feature = "kmsg")]
+        if buf_id == Some(LogId::Kernel) {
+            let _ = write!(writer, "<{}>", kmsg_severity(record.level()));
+        }
+
+        let (custom_format, output_format) = self.active_format();
+        if output_format == OutputFormat::Plain {
+            let _ = config.write_prefixes(&mut writer, record.level(), time);
+        }
+
+        let mut body = NewlineWriter {
+            writer: &mut writer,
+            handling: config.newline_handling,
+        };
 
         // If a custom tag is used, add the module path to the message.
         // Use PlatformLogWriter to output chunks if they exceed max size.
-        let _ = match (custom_tag, &config.custom_format) {
-            (_, Some(format)) => format(&mut writer, record),
-            (Some(_), _) => fmt::write(
-                &mut writer,
-                format_args!("{}: {}", module_path, *record.args()),
-            ),
-            _ => fmt::write(&mut writer, *record.args()),
+        let formatted = match (custom_format, output_format) {
+            (Some(format), _) => format(&mut body, record),
+            (_, OutputFormat::Json) => json::write_record(&mut body, record, config, repeat),
+            (_, OutputFormat::Logfmt) => logfmt::write_record(&mut body, record, config, repeat),
+            _ if prefix_module_path => write!(body, "{module_path}: ").and_then(|()| {
+                binary::write_message(&mut body, *record.args(), config.binary_encoding)
+            }),
+            _ => binary::write_message(&mut body, *record.args(), config.binary_encoding),
+        };
+
+        #[cfg(feature = "kv")]
+        let formatted = formatted.and_then(|()| {
+            if config.pretty_kv && custom_format.is_none() && output_format == OutputFormat::Plain {
+                kv::write_pretty(&mut body, record, config)
+            } else {
+                Ok(())
+            }
+        });
+
+        // Structured formats hold the repeat as a field instead.
+        let formatted = match repeat {
+            Some(repeat) if custom_format.is_none() && output_format == OutputFormat::Plain => {
+                formatted.and_then(|()| write!(writer, " #{repeat}"))
+            }
+            _ => formatted,
         };
 
+        // A `Display` or `Debug` impl failed: keep what was written so far,
+        // but make the message visibly incomplete.
+        if formatted.is_err() {
+            let _ = writer.write_str(FORMAT_ERROR_MARKER);
+        }
+
         // output the remaining message (this would usually be the most common case)
         writer.flush();
     }
 
-    fn flush(&self) {}
+    fn fill_tag_bytes(&self, array: &mut [MaybeUninit<u8>], tag: &[u8]) {
+        self.config().fill_tag_bytes(array, tag);
+    }
 }
 
-impl AndroidLogger {
-    fn fill_tag_bytes(&self, array: &mut [MaybeUninit<u8>], tag: &[u8]) {
-        if tag.len() > LOGGING_TAG_MAX_LEN {
-            for (input, output) in tag
-                .iter()
-                .take(LOGGING_TAG_MAX_LEN - 2)
-                .chain(b"..\0".iter())
-                .zip(array.iter_mut())
-            {
-                output.write(*input);
-            }
-        } else {
-            for (input, output) in tag.iter().chain(b"\0".iter()).zip(array.iter_mut()) {
-                output.write(*input);
-            }
-        }
+/// Returns the syslog severity used as `/dev/kmsg` prefix for `level`.
+#[cfg(feature = "kmsg")]
+fn kmsg_severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Returns the value of the Android priority `level` is logged with, without
+/// [`Config::trace_as_debug()`].
+fn priority_value(level: Level) -> i32 {
+    match level {
+        Level::Trace => 2,
+        Level::Debug => 3,
+        Level::Info => 4,
+        Level::Warn => 5,
+        Level::Error => 6,
+    }
+}
+
+/// Returns the icon prefixing messages of `level`, see
+/// [`Config::with_level_icons()`].
+fn level_icon(level: Level) -> &'static str {
+    match level {
+        Level::Error => "❌",
+        Level::Warn => "⚠️",
+        Level::Info => "ℹ️",
+        Level::Debug => "🐛",
+        Level::Trace => "🔍",
     }
 }
 
 /// Filter for android logger.
-#[derive(Default)]
+///
+/// A `Config` can be cloned, e.g. to create several loggers from a single
+/// template. Clones share the callbacks (e.g. of [`Config::format()`]), but
+/// each logger has its own state of the features tracking the records it
+/// logs (e.g. [`Config::dedup_window()`]), and its own session id (see
+/// [`Config::with_session_id()`]).
+#[derive(Clone, Default)]
 pub struct Config {
     log_level: Option<LevelFilter>,
     buf_id: Option<LogId>,
     filter: Option<env_filter::Filter>,
+    explain_drops: bool,
+    directives: Option<Directives>,
     tag: Option<CString>,
     custom_format: Option<FormatFn>,
+    format_presets: Vec<(String, FormatFn)>,
+    trace_as_debug: bool,
+    dedup: Option<DedupWindow>,
+    output_format: OutputFormat,
+    on_filtered: Option<FilteredFn>,
+    quiet_init_failure: bool,
+    numeric_priority: bool,
+    tag_from_crate: bool,
+    base_kv: Vec<(String, String)>,
+    silence_internal: bool,
+    max_chunks: Option<usize>,
+    format_memory_cap: Option<usize>,
+    serialize_output: bool,
+    timestamp: bool,
+    clock: Option<Arc<dyn Clock>>,
+    newline_handling: NewlineHandling,
+    display_tag_len: Option<usize>,
+    heartbeat: Option<HeartbeatConfig>,
+    #[cfg(feature = "kv")]
+    pretty_kv: bool,
+    #[cfg(feature = "kv")]
+    kv_allowlist: Option<Vec<String>>,
+    #[cfg(feature = "kv")]
+    kv_blocklist: Vec<String>,
+    panic_buffer: Option<LogId>,
+    no_split: bool,
+    level_icons: bool,
+    module_buffers: Vec<(String, LogId)>,
+    labels: Vec<(String, LabelFn)>,
+    label_buffers: Vec<(String, LogId)>,
+    mirror: Option<Arc<dyn Log>>,
+    #[cfg(not(target_os = "android"))]
+    dev_ergonomics: bool,
+    #[cfg(not(target_os = "android"))]
+    host_stream_by_level: bool,
+    tee_buffers: Vec<LogId>,
+    #[cfg(unix)]
+    socket: Option<SocketTee>,
+    first_n: Option<FirstN>,
+    /// Value of the minimum Android priority, e.g. `4` for `INFO`.
+    min_priority: Option<i32>,
+    without_tag: bool,
+    level_tags: Vec<(Level, String)>,
+    trim_trailing_newline: bool,
+    log_thread_lifecycle: bool,
+    binary_encoding: BinaryEncoding,
+    total_payload_len: Option<usize>,
+    build_id: Option<String>,
+    profile: Option<&'static str>,
+    tag_abbrev_format: Option<AbbrevFormat>,
+    tag_collisions: Option<TagCollisions>,
+    tag_rate_limit: Option<TagRateLimit>,
+    tag_counters: Option<TagCounters>,
+    schema_version: Option<u32>,
+    tooling_header: bool,
+    blocked_targets: Vec<String>,
+    unmanaged_max_level: bool,
+    inter_log_delta: bool,
+    always_include_module_path: bool,
+    subsystem: Option<String>,
+    backend: Option<Arc<dyn LogBackend>>,
+    category: Option<String>,
+    correlation_id: bool,
+    screen_name: bool,
+    skip_whitespace_only_chunks: bool,
+    session_id: bool,
+    /// Generated by [`AndroidLogger::new()`] if `session_id` is set.
+    session: Option<String>,
+    suppressions: Option<Suppressions>,
+    chatty: Option<Chatty>,
+    empty_message: EmptyMessage,
+    #[cfg(target_os = "android")]
+    honor_tag_properties: bool,
+}
+
+/// Whether `target` is the module `module`, or one of its submodules.
+fn is_in_module(target: &str, module: &str) -> bool {
+    target
+        .strip_prefix(module)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// How module paths are abbreviated into tags, see
+/// [`Config::with_tag_abbrev_format()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AbbrevFormat {
+    /// Joins the segments of the module path, instead of `::`.
+    pub separator: &'static str,
+    /// Marks abbreviated segments, after their first character.
+    pub ellipsis: &'static str,
+}
+
+impl AbbrevFormat {
+    /// Renders `module_path`, abbreviating its leading segments until it fits
+    /// into `max_len` bytes, if possible.
+    fn abbreviate(&self, module_path: &str, max_len: usize) -> String {
+        let segments: Vec<&str> = module_path.split("::").collect();
+        let mut abbreviated = 0;
+        loop {
+            let mut tag = String::new();
+            for (i, segment) in segments.iter().enumerate() {
+                if i > 0 {
+                    tag.push_str(self.separator);
+                }
+                match segment.chars().next() {
+                    Some(first) if i < abbreviated => {
+                        tag.push(first);
+                        tag.push_str(self.ellipsis);
+                    }
+                    _ => tag.push_str(segment),
+                }
+            }
+            if tag.len() <= max_len || abbreviated + 1 >= segments.len() {
+                return tag;
+            }
+            abbreviated += 1;
+        }
+    }
+}
+
+/// Built-in formats of the log output.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum OutputFormat {
+    /// Just the message (prefixed with the module path, if a custom tag is
+    /// used).
+    #[default]
+    Plain,
+
+    /// A JSON object per record.
+    Json,
+
+    /// A logfmt line per record.
+    Logfmt,
 }
 
 impl Config {
@@ -293,6 +1023,21 @@ impl Config {
         self
     }
 
+    /// Sets the maximum log level from whether the app is debuggable, i.e.
+    /// `Debug` for debuggable apps and `Info` otherwise.
+    ///
+    /// android_logger has no access to the application info, so the flag has
+    /// to be passed in, e.g. from `ApplicationInfo.FLAG_DEBUGGABLE` obtained
+    /// via JNI. Like [`Config::with_max_level()`], this replaces any maximum
+    /// level set before.
+    pub fn with_debuggable(self, debuggable: bool) -> Self {
+        self.with_max_level(if debuggable {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Info
+        })
+    }
+
     /// Changes the Android logging system buffer to be used.
     ///
     /// By default, logs are sent to the [`Main`] log. Other logging buffers may
@@ -304,340 +1049,3026 @@ impl Config {
         self
     }
 
-    fn filter_matches(&self, record: &Record) -> bool {
-        if let Some(ref filter) = self.filter {
-            filter.matches(record)
-        } else {
-            true
+    /// Writes the log messages to `backend` instead of the Android logging
+    /// system, e.g. to use this crate's filtering and formatting with another
+    /// transport, or on other platforms.
+    ///
+    /// `backend` gets the messages of the records logged by this logger, once
+    /// formatted and split, with the buffers and priorities they would be
+    /// logged with, as well as its other messages (e.g. of
+    /// [`AndroidLogger::hexdump()`], panics, or [`Config::with_heartbeat()`]).
+    /// Messages captured by [`test_capture()`] aren't written to it. The
+    /// diagnostics of the logger itself (see [`INTERNAL_TAG`]) and
+    /// [`write_cstr()`] still go to the Android logging system.
+    pub fn with_backend(mut self, backend: Arc<dyn LogBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Also passes the records accepted by the filter to `logger`, in addition
+    /// to logging them to logcat.
+    ///
+    /// This allows to forward logs to another backend without installing a
+    /// second global logger, e.g. to OpenTelemetry through the `log::Log`
+    /// bridge of the `opentelemetry-appender-log` crate. The records are passed
+    /// as is, so `logger` gets their level, target, message and key-values
+    /// (with the `kv` feature), rather than the formatted logcat output.
+    /// [`Log::flush()`] is forwarded as well. Nothing is passed to `logger`
+    /// with the `deny-logging` feature.
+    ///
+    /// `logger` gets the records before the suppressions of the logger itself,
+    /// so it also gets the ones dropped by [`Config::dedup_window()`],
+    /// [`Config::log_first_n()`] and [`Config::with_rate_limit_per_tag()`],
+    /// and may apply its own policy.
+    ///
+    /// `logger` is called synchronously on the logging thread. Loggers doing
+    /// expensive work, like exporting over the network, should hand records
+    /// off to a background task, as exporters usually do.
+    pub fn mirror_to(mut self, logger: impl Log + 'static) -> Self {
+        self.mirror = Some(Arc::new(logger));
+        #[cfg(not(target_os = "android"))]
+        {
+            self.dev_ergonomics = false;
         }
+        self
     }
 
-    pub fn with_filter(mut self, filter: env_filter::Filter) -> Self {
-        self.filter = Some(filter);
+    /// Makes the same binary pleasant to run both on a device and on a
+    /// development host.
+    ///
+    /// On Android, this changes nothing: records are logged to logcat. On
+    /// other targets, where there is no logcat, every record accepted by the
+    /// filter is also written to stderr (but see
+    /// [`Config::host_stream_by_level()`]) as a line colored by its level (red
+    /// errors, yellow warnings, green infos, blue debug and magenta trace
+    /// records), e.g. `ERROR my_app::net: connection lost`. This replaces the
+    /// logger set with [`Config::mirror_to()`].
+    pub fn with_dev_ergonomics(self) -> Self {
+        #[cfg(not(target_os = "android"))]
+        {
+            self.with_dev_ergonomics_to(std::io::stderr(), std::io::stdout())
+        }
+        #[cfg(target_os = "android")]
         self
     }
 
-    pub fn with_tag<S: Into<Vec<u8>>>(mut self, tag: S) -> Self {
-        self.tag = Some(CString::new(tag).expect("Can't convert tag to CString"));
+    /// Makes [`Config::with_dev_ergonomics()`] write errors and warnings to
+    /// stderr, and info, debug and trace records to stdout, instead of
+    /// writing everything to stderr.
+    ///
+    /// This matches the expectations of tools treating stderr as the
+    /// problems of a program, and its stdout as its regular output. Programs
+    /// whose stdout is their data output (e.g. command-line tools) should
+    /// keep the default. This can be called before or after
+    /// [`Config::with_dev_ergonomics()`], and changes nothing on Android.
+    pub fn host_stream_by_level(self, enabled: bool) -> Self {
+        #[cfg(not(target_os = "android"))]
+        {
+            let mut config = self;
+            config.host_stream_by_level = enabled;
+            if config.dev_ergonomics {
+                config.with_dev_ergonomics()
+            } else {
+                config
+            }
+        }
+        #[cfg(target_os = "android")]
+        {
+            let _ = enabled;
+            self
+        }
+    }
+
+    /// Also writes every log message to the Unix domain socket at `path`, as
+    /// a line `<priority>/<tag>: <message>`, e.g. `I/my_app: started`, for a
+    /// local log-aggregation daemon.
+    ///
+    /// Lines are the messages as written to logcat, once formatted and split
+    /// (see [`Config::max_chunks_per_record()`]). The socket is connected to
+    /// when the first message is logged, and writes never block: lines are
+    /// dropped while the reader can't keep up, rather than buffered. If
+    /// connecting or writing fails (e.g. the daemon isn't running, or
+    /// exited), lines are dropped, and connecting is retried for the next
+    /// message at most once per second. The process must ignore `SIGPIPE`,
+    /// as Rust programs and Android apps do, so a daemon exiting doesn't
+    /// kill it.
+    ///
+    /// The socket gets a copy of the messages written to logcat, or to the
+    /// [`Config::with_backend()`] backend if any, so nothing is written to
+    /// it inside [`test_capture()`], or with the `deny-logging` feature.
+    #[cfg(unix)]
+    pub fn with_socket(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.socket = Some(SocketTee::new(path.into()));
         self
     }
 
-    /// Sets the format function for formatting the log output.
+    /// Also writes every log message to the `buf_id` buffer, e.g. to copy logs
+    /// to [`LogId::Crash`].
+    ///
+    /// Each message is written to its usual buffer (see
+    /// [`Config::with_log_buffer()`]) first, then to the buffers added with
+    /// this, in the order they were added. This order is guaranteed, so
+    /// messages can be correlated across buffers.
+    pub fn tee_to_buffer(mut self, buf_id: LogId) -> Self {
+        self.tee_buffers.push(buf_id);
+        self
+    }
+
+    /// Sends the logs of `module_prefix` (e.g. `my_app::radio`) and its
+    /// submodules to the `buf_id` buffer, instead of the one set with
+    /// [`Config::with_log_buffer()`].
+    ///
+    /// Modules are matched against the target of records, which is their
+    /// module path by default. If several prefixes match, the longest one
+    /// wins. Note, that most buffers are only writable by privileged
+    /// processes (see [`accessible_buffers()`]), and writes to the others
+    /// fail silently.
+    pub fn route_module_to_buffer(mut self, module_prefix: &str, buf_id: LogId) -> Self {
+        self.module_buffers.push((module_prefix.to_owned(), buf_id));
+        self
+    }
+
+    /// Defines the label `name`, which the records `matches` returns true for
+    /// have, so that rules (e.g. [`Config::route_label_to_buffer()`]) can
+    /// apply to all of them at once, e.g.:
+    ///
     /// ```
-    /// # use android_logger::Config;
-    /// android_logger::init_once(
-    ///     Config::default()
-    ///         .with_max_level(log::LevelFilter::Trace)
-    ///         .format(|f, record| write!(f, "my_app: {}", record.args()))
-    /// )
+    /// use android_logger::{Config, LogId};
+    ///
+    /// let config = Config::default()
+    ///     .with_label("network", |record| {
+    ///         record.target().starts_with("hyper") || record.target().starts_with("my_app::http")
+    ///     })
+    ///     .route_label_to_buffer("network", LogId::Radio);
     /// ```
-    pub fn format<F>(mut self, format: F) -> Self
+    ///
+    /// `matches` is called for each record reaching a rule using the label,
+    /// every time it does: it should be cheap, and is best kept to the
+    /// metadata of the record rather than its message. Defining a label
+    /// again replaces its predicate.
+    pub fn with_label<F>(mut self, name: &str, matches: F) -> Self
     where
-        F: Fn(&mut dyn fmt::Write, &Record) -> fmt::Result + Sync + Send + 'static,
+        F: Fn(&Record) -> bool + Sync + Send + 'static,
     {
-        self.custom_format = Some(Box::new(format));
+        self.labels.retain(|(label, _)| label != name);
+        self.labels.push((name.to_owned(), Arc::new(matches)));
         self
     }
-}
 
-pub struct PlatformLogWriter<'a> {
-    #[cfg(target_os = "android")]
-    priority: LogPriority,
-    #[cfg(not(target_os = "android"))]
-    priority: Level,
-    #[cfg(target_os = "android")]
-    buf_id: Option<log_ffi::log_id_t>,
-    #[cfg(not(target_os = "android"))]
-    buf_id: Option<LogId>,
-    len: usize,
-    last_newline_index: usize,
-    tag: &'a CStr,
-    buffer: [MaybeUninit<u8>; LOGGING_MSG_MAX_LEN + 1],
-}
+    /// Sends the logs with the label `label` (see [`Config::with_label()`])
+    /// to the `buf_id` buffer.
+    ///
+    /// Rules are checked in the order they were added, and the first one
+    /// matching wins. Labels take precedence over modules (see
+    /// [`Config::route_module_to_buffer()`]), but not over
+    /// [`buffer_scope()`]. A rule using an undefined label matches nothing.
+    pub fn route_label_to_buffer(mut self, label: &str, buf_id: LogId) -> Self {
+        self.label_buffers.push((label.to_owned(), buf_id));
+        self
+    }
 
-impl<'a> PlatformLogWriter<'a> {
+    /// Returns the buffer for records of `target`.
+    /// Returns the buffer `record` is logged to.
+    fn buffer_of(&self, record: &Record) -> Option<LogId> {
+        #[cfg(feature = "kv")]
+        let buf_id = kv::buffer_override(record).or_else(buffer_scope::current);
+        #[cfg(not(feature = "kv"))]
+        let buf_id = buffer_scope::current();
+        buf_id
+            .or_else(|| self.label_buffer(record))
+            .or_else(|| self.buffer_for(record.target()))
+    }
+
+    fn buffer_for(&self, target: &str) -> Option<LogId> {
+        self.module_buffers
+            .iter()
+            .filter(|(prefix, _)| is_in_module(target, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|&(_, buf_id)| buf_id)
+            .or(self.buf_id)
+    }
+
+    /// Drops all records of the `targets` and of their submodules, whatever
+    /// the filter, e.g. to silence a noisy dependency.
+    ///
+    /// An entry matches targets equal to it, or starting with it followed by
+    /// `::`: `hyper` blocks `hyper` and `hyper::client`, but not `hyperlocal`.
+    /// This is checked before the filter, so blocked records are never passed
+    /// to [`Config::on_filtered()`], nor to [`Config::mirror_to()`].
+    pub fn block_targets(mut self, targets: Vec<String>) -> Self {
+        self.blocked_targets = targets;
+        self
+    }
+
+    /// Whether records of `target` are dropped by [`Config::block_targets()`].
+    fn is_blocked(&self, target: &str) -> bool {
+        self.blocked_targets
+            .iter()
+            .any(|blocked| is_in_module(target, blocked))
+    }
+
+    /// Emits [`Level::Trace`] records with the `DEBUG` priority instead of
+    /// `VERBOSE`.
+    ///
+    /// Many production devices filter out `VERBOSE` logs entirely, so this
+    /// allows `trace!` logs to still be seen in logcat.
+    pub fn trace_as_debug(mut self, enabled: bool) -> Self {
+        self.trace_as_debug = enabled;
+        self
+    }
+
+    /// Suppresses messages identical to one emitted within the last `window`.
+    ///
+    /// Unlike collapsing consecutive duplicates, this also catches a message
+    /// recurring from several call sites with other logs in between. Up to
+    /// `capacity` recently emitted messages are remembered; once more distinct
+    /// messages are seen within the window, the oldest ones are forgotten
+    /// (and may be emitted again).
+    ///
+    /// Messages are compared by a 64-bit hash of their level and text, so two
+    /// distinct messages colliding on the same hash would be treated as
+    /// duplicates. This is extremely unlikely, but not impossible.
+    ///
+    /// At most once per window, the next emitted message is preceded by a
+    /// summary of how many duplicates were suppressed since the last one,
+    /// e.g. `suppressed 3 duplicate messages`. The summary is written in the
+    /// active format, as the message of a record of the same level and target
+    /// as the next one (e.g. as a JSON object with [`Config::format_json()`]).
+    pub fn dedup_window(mut self, window: Duration, capacity: usize) -> Self {
+        self.dedup = Some(DedupWindow::new(window, capacity));
+        self
+    }
+
+    /// Sets what is logged for records whose message is empty, e.g. records
+    /// only made of key-values (with the `kv` feature), see [`EmptyMessage`].
+    ///
+    /// By default ([`EmptyMessage::KvOnly`]), they're logged as is, so only
+    /// structured formats and `Config::pretty_kv()` show anything but the
+    /// prefixes. Checking whether a message is empty formats it until its
+    /// first byte.
+    pub fn empty_message_behavior(mut self, behavior: EmptyMessage) -> Self {
+        self.empty_message = behavior;
+        self
+    }
+
+    /// Appends a ` #<n>` token to the `n`th consecutive repeat of a message,
+    /// so that logd doesn't collapse them, e.g. `tick`, `tick #1`, `tick #2`.
+    ///
+    /// logd's "chatty" feature replaces identical consecutive messages with a
+    /// single `identical N lines` notice, which hides how many times, and
+    /// when, an expected message was logged. This intentionally defeats it,
+    /// for cases where every occurrence matters, at the cost of the space the
+    /// repeats take in the log buffer. Messages are compared by a hash of
+    /// their level and text, regardless of their tag.
+    ///
+    /// Only the last chunk of a message split into several gets the token.
+    /// With [`Config::format_json()`] and [`Config::format_logfmt()`], the
+    /// repeat is a `repeat` field instead, e.g. `"repeat":1`, and messages
+    /// with a custom [`Config::format()`] are left as they are.
+    pub fn defeat_chatty(mut self, enabled: bool) -> Self {
+        self.chatty = enabled.then(Chatty::default);
+        self
+    }
+
+    /// Logs a summary of the records dropped by [`Config::dedup_window()`],
+    /// [`Config::log_first_n()`] and [`Config::with_rate_limit_per_tag()`]
+    /// since the previous summary, e.g.
+    /// `suppressed: 7 deduped, 3 over first-n limit`, on [`Log::flush()`] and
+    /// [`shutdown()`].
+    ///
+    /// This gives an accounting of what was dropped at natural boundaries,
+    /// e.g. when going to the background. The summary is logged at the info
+    /// level, under the configured tag (or [`INTERNAL_TAG`]), and only if
+    /// anything was dropped.
+    pub fn summarize_suppressions(mut self, enabled: bool) -> Self {
+        self.suppressions = enabled.then(Suppressions::default);
+        self
+    }
+
+    /// Prefixes messages with the number of records emitted under their tag
+    /// before, e.g. `#42 `, starting from `#0`.
+    ///
+    /// As logd may drop messages of a tag (e.g. when it logs too much), a gap
+    /// in the count of a tag reveals that some of its messages were lost. A
+    /// record split into several messages is counted once, and records
+    /// dropped by the logger itself (e.g. by [`Config::dedup_window()`]) aren't
+    /// counted. Up to 1024 tags are counted, later ones get no prefix. This
+    /// comes before any other prefix, and only applies to the plain format.
+    pub fn with_per_tag_counter(mut self, enabled: bool) -> Self {
+        self.tag_counters = enabled.then(TagCounters::default);
+        self
+    }
+
+    /// Drops the records of a tag once `max` of them were emitted within
+    /// `interval`, until the interval is over.
+    ///
+    /// Each tag (after truncation, see [`Config::with_display_tag_len()`])
+    /// has its own budget, so that a chatty component can't starve the
+    /// others. Up to 256 tags are tracked at once: when a new tag is seen past
+    /// that, the tags whose interval is over are forgotten, or the one whose
+    /// interval started first if there are none, so its budget starts over.
+    pub fn with_rate_limit_per_tag(mut self, max: u32, interval: Duration) -> Self {
+        self.tag_rate_limit = Some(TagRateLimit::new(max, interval));
+        self
+    }
+
+    /// Logs only the first `n` occurrences of each message, for the lifetime
+    /// of the logger, e.g. for diagnostics repeated on every startup step.
+    ///
+    /// The first occurrence dropped is replaced with a notice that further
+    /// ones are suppressed, e.g.
+    /// `suppressing further occurrences of: connecting to host`. Unlike
+    /// [`Config::dedup_window()`], occurrences are never forgotten over time.
+    /// Only the last 256 distinct messages seen are counted though: once more
+    /// are seen, the least recently seen ones are forgotten (and may be logged
+    /// `n` times again). Messages are compared by a hash of their level and
+    /// text, as for dedup windows. The notice is written in the active format,
+    /// like the summary of [`Config::dedup_window()`].
+    pub fn log_first_n(mut self, n: u32) -> Self {
+        self.first_n = Some(FirstN::new(n));
+        self
+    }
+
+    /// Sets whether [`init_once()`] calls [`log::set_max_level()`] with the
+    /// level set by [`Config::with_max_level()`] (or a tag property, see
+    /// `Config::honor_tag_properties()`), which it does by default.
+    ///
+    /// With `false`, the global maximum level is left to the caller, e.g.
+    /// when another component manages it. Records above it are then dropped
+    /// by the `log` macros before reaching the logger, whatever its filter.
+    pub fn manage_max_level(mut self, manage: bool) -> Self {
+        self.unmanaged_max_level = !manage;
+        self
+    }
+
+    /// Disables the warning written to logcat when [`init_once()`] fails to
+    /// install the logger.
+    pub fn quiet_init_failure(mut self, quiet: bool) -> Self {
+        self.quiet_init_failure = quiet;
+        self
+    }
+
+    /// Prefixes each message with the numeric value of the Android priority
+    /// it is logged with (e.g. `6` for `ERROR`), for machine parsing.
+    ///
+    /// Like other prefixes, this is not added to structured formats (e.g.
+    /// [`Config::format_json()`]).
+    pub fn with_numeric_priority(mut self, enabled: bool) -> Self {
+        self.numeric_priority = enabled;
+        self
+    }
+
+    /// Truncates tags longer than `len` bytes, instead of the maximum length
+    /// supported by liblog, e.g. for dashboards displaying only the start of
+    /// tags.
+    ///
+    /// Like tags exceeding the liblog maximum, truncated tags end with `..`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is less than 3 (the ellipsis and one more byte), or more
+    /// than the liblog maximum of 23 bytes.
+    pub fn with_display_tag_len(mut self, len: usize) -> Self {
+        assert!(
+            (3..=LOGGING_TAG_MAX_LEN).contains(&len),
+            "display tag length must be in 3..={LOGGING_TAG_MAX_LEN}, got {len}"
+        );
+        self.display_tag_len = Some(len);
+        self
+    }
+
+    /// Logs `message` every `interval`, at the given `level`, to confirm in
+    /// logcat that the process is alive even when it's quiet otherwise.
+    ///
+    /// The heartbeat is logged under the configured tag (or `android_logger`
+    /// if none is set), regardless of the filter and maximum level. It is
+    /// opt-in, as it costs a background thread, started when the logger is
+    /// created (e.g. by [`init_once()`]), and stopped when it's flushed (see
+    /// [`Log::flush()`]) or dropped. It's never started with the
+    /// `deny-logging` feature.
+    pub fn with_heartbeat(
+        mut self,
+        interval: Duration,
+        level: Level,
+        message: impl Into<String>,
+    ) -> Self {
+        self.heartbeat = Some(HeartbeatConfig {
+            interval,
+            level,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Appends the key-values of each record to its message, one per line,
+    /// indented, e.g.:
+    ///
+    /// ```text
+    /// login failed
+    ///     user: alice
+    ///     attempt: 3
+    /// ```
+    ///
+    /// This is meant for reading logs during development: it makes messages
+    /// longer, and more likely to be split into several log messages. It
+    /// doesn't apply to custom and structured formats (e.g.
+    /// [`Config::format_json()`]), which handle key-values themselves.
+    #[cfg(feature = "kv")]
+    pub fn pretty_kv(mut self, enabled: bool) -> Self {
+        self.pretty_kv = enabled;
+        self
+    }
+
+    /// Only emits the record key-values whose key is in `keys`, dropping the
+    /// others.
+    ///
+    /// This applies wherever key-values are written: structured formats (e.g.
+    /// [`Config::format_json()`]) and [`Config::pretty_kv()`]. It doesn't
+    /// apply to [`Config::with_base_kv()`], which are set by the app itself.
+    /// A key both allowed and in [`Config::kv_blocklist()`] is dropped.
+    #[cfg(feature = "kv")]
+    pub fn kv_allowlist(mut self, keys: Vec<String>) -> Self {
+        self.kv_allowlist = Some(keys);
+        self
+    }
+
+    /// Drops the record key-values whose key is in `keys`, e.g. to keep
+    /// sensitive fields out of logcat.
+    ///
+    /// This applies to the same key-values as [`Config::kv_allowlist()`],
+    /// and takes precedence over it.
+    #[cfg(feature = "kv")]
+    pub fn kv_blocklist(mut self, keys: Vec<String>) -> Self {
+        self.kv_blocklist = keys;
+        self
+    }
+
+    /// Whether the record key-value `key` is emitted, according to
+    /// [`Config::kv_allowlist()`] and [`Config::kv_blocklist()`]. Reserved
+    /// keys never are.
+    #[cfg(feature = "kv")]
+    fn emits_kv(&self, key: &str) -> bool {
+        key != kv::BUFFER_KEY
+            && self
+                .kv_allowlist
+                .as_ref()
+                .is_none_or(|allowed| allowed.iter().any(|k| k == key))
+            && !self.kv_blocklist.iter().any(|k| k == key)
+    }
+
+    /// Logs panics to the `buf_id` buffer (e.g. [`LogId::Crash`]), instead of
+    /// the buffer of other logs.
+    ///
+    /// This only applies to panics logged by the hook installed with
+    /// [`install_panic_hook()`].
+    pub fn with_panic_buffer(mut self, buf_id: LogId) -> Self {
+        self.panic_buffer = Some(buf_id);
+        self
+    }
+
+    /// Drops a single newline ending a message, e.g. from code ported from
+    /// `println!`-style output, so logcat doesn't show an empty line after it.
+    ///
+    /// Newlines inside messages, and any but the last one at their end, are
+    /// kept.
+    pub fn trim_trailing_newline(mut self, trim: bool) -> Self {
+        self.trim_trailing_newline = trim;
+        self
+    }
+
+    /// Logs a `thread ... started` marker the first time a thread logs, and a
+    /// `thread ... ended` marker when it exits, with the tag and buffer of
+    /// that first message.
+    ///
+    /// Threads are named by their name, or their [`ThreadId`] if unnamed.
+    /// The end marker is best-effort: it is logged while the thread-local
+    /// storage of the thread is destroyed, which doesn't happen for the main
+    /// thread, nor for threads the process exits before, and may happen after
+    /// other thread-local values needed to log it are gone. Threads which
+    /// never log don't get any marker.
+    ///
+    /// [`ThreadId`]: std::thread::ThreadId
+    pub fn log_thread_lifecycle(mut self, enabled: bool) -> Self {
+        self.log_thread_lifecycle = enabled;
+        self
+    }
+
+    /// Applies the level of the `log.tag.<tag>` system property (or the
+    /// persistent `persist.log.tag.<tag>` one, which survives reboots) for
+    /// the tag set with [`Config::with_tag()`], when installed with
+    /// [`init_once()`].
+    ///
+    /// These properties are how Android configures the verbosity of a tag,
+    /// e.g. with `adb shell setprop persist.log.tag.MyApp D`. If the property
+    /// holds a valid level, it replaces the configured filter (like
+    /// [`poll_system_property()`]) and [`log::set_max_level()`] is set to it,
+    /// so the `log` macros don't drop the messages the property allows.
+    /// Otherwise the configuration applies unchanged.
+    ///
+    /// The properties are only read once, on initialization.
     #[cfg(target_os = "android")]
-    pub fn new_with_priority(
-        buf_id: Option<LogId>,
-        priority: log_ffi::LogPriority,
-        tag: &CStr,
-    ) -> PlatformLogWriter<'_> {
-        #[allow(deprecated)] // created an issue #35 for this
-        PlatformLogWriter {
-            priority,
-            buf_id: LogId::to_native(buf_id),
-            len: 0,
-            last_newline_index: 0,
-            tag,
-            buffer: uninit_array(),
+    pub fn honor_tag_properties(mut self, honor: bool) -> Self {
+        self.honor_tag_properties = honor;
+        self
+    }
+
+    /// Prefixes messages with the correlation id of the thread logging them,
+    /// as `[req=<id>] `, if it has one (see [`set_correlation_id()`]).
+    ///
+    /// Like other prefixes, this only applies to the plain output format, and
+    /// comes after the ones enabled with e.g. [`Config::with_timestamp()`].
+    pub fn with_correlation_id(mut self, enabled: bool) -> Self {
+        self.correlation_id = enabled;
+        self
+    }
+
+    /// Limits the total length of the payload of each log message, that is of
+    /// the priority, tag and message together, to `len` bytes.
+    ///
+    /// Messages are split into chunks of up to 4000 bytes, regardless of the
+    /// length of their tag, which some (older) devices cut short when the
+    /// tag is long. With this, the chunks are shrunk by the length of the tag
+    /// and the overhead of the priority and `\0` terminators, so each log
+    /// message fits into `len` bytes. Chunks are never longer than 4000 bytes,
+    /// nor shorter than a single byte.
+    pub fn with_total_payload_len(mut self, len: usize) -> Self {
+        self.total_payload_len = Some(len);
+        self
+    }
+
+    /// Returns the maximum length of a message chunk logged with `tag`.
+    fn message_len_for_tag(&self, tag: &CStr) -> usize {
+        /// The priority byte, and the `\0`s ending the tag and the message.
+        const OVERHEAD: usize = 3;
+
+        match self.total_payload_len {
+            Some(total) => total
+                .saturating_sub(tag.count_bytes() + OVERHEAD)
+                .clamp(1, LOGGING_MSG_MAX_LEN),
+            None => LOGGING_MSG_MAX_LEN,
         }
     }
 
+    /// Prefixes messages with the name of the screen currently shown, as
+    /// `[screen=<name>] `, if one is set (see [`set_current_screen()`]).
+    ///
+    /// This comes before the correlation id (see
+    /// [`Config::with_correlation_id()`]), and only applies to the plain
+    /// format.
+    pub fn with_screen_name(mut self, enabled: bool) -> Self {
+        self.screen_name = enabled;
+        self
+    }
+
+    /// Encodes messages holding binary data (nul bytes, or control characters
+    /// other than whitespace), e.g. raw protocol frames, with `encoding`.
+    ///
+    /// The Android logging system drops everything after a nul byte, so such
+    /// messages are silently cut short otherwise. Encoded messages are
+    /// unreadable in logcat, but can be decoded back. Only the message itself
+    /// is encoded, and only with the plain output format: prefixes (e.g. the
+    /// module path) stay readable. The default is [`BinaryEncoding::None`].
+    pub fn binary_encoding(mut self, encoding: BinaryEncoding) -> Self {
+        self.binary_encoding = encoding;
+        self
+    }
+
+    /// Drops the log messages made of whitespace only, e.g. the last one of a
+    /// long message split before its trailing spaces and newlines, which
+    /// would show as an empty line in logcat.
+    ///
+    /// This applies to each message a record is split into, so records made
+    /// of whitespace only aren't logged at all.
+    pub fn skip_whitespace_only_chunks(mut self, skip: bool) -> Self {
+        self.skip_whitespace_only_chunks = skip;
+        self
+    }
+
+    /// Sets how newlines in messages are logged, e.g. for log consumers
+    /// treating each line as a separate record.
+    ///
+    /// This applies to the formatted message only, not to the prefixes
+    /// (e.g. [`Config::with_timestamp()`]). The default is
+    /// [`NewlineHandling::Keep`].
+    pub fn newline_handling(mut self, handling: NewlineHandling) -> Self {
+        self.newline_handling = handling;
+        self
+    }
+
+    /// Prefixes each message with the time it was logged at, as an RFC 3339
+    /// UTC timestamp with milliseconds (e.g. `2023-11-14T22:13:20.123Z`).
+    ///
+    /// logcat records the time of each message anyway, so this is mostly
+    /// useful when the messages are collected by other means. Like other
+    /// prefixes, this is not added to structured formats (e.g.
+    /// [`Config::format_json()`]).
+    pub fn with_timestamp(mut self, enabled: bool) -> Self {
+        self.timestamp = enabled;
+        self
+    }
+
+    /// Sets the [`Clock`] used by time-based features, instead of the
+    /// [`SystemClock`].
+    ///
+    /// This is mostly useful for testing these features deterministically.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    fn clock(&self) -> &dyn Clock {
+        match &self.clock {
+            Some(clock) => clock.as_ref(),
+            None => &SystemClock,
+        }
+    }
+
+    /// Prefixes each message with an icon for the level of its record, to make
+    /// logs easier to scan during development:
+    ///
+    /// | Level   | Icon  |
+    /// |---------|-------|
+    /// | `Error` | ❌    |
+    /// | `Warn`  | ⚠️    |
+    /// | `Info`  | ℹ️    |
+    /// | `Debug` | 🐛    |
+    /// | `Trace` | 🔍    |
+    ///
+    /// Icons take several bytes each, which count toward the length of the log
+    /// message. Like other prefixes, they are not added to structured formats
+    /// (e.g. [`Config::format_json()`]).
+    pub fn with_level_icons(mut self, enabled: bool) -> Self {
+        self.level_icons = enabled;
+        self
+    }
+
+    /// Writes the configured prefixes of a message, each followed by a space.
+    fn write_prefixes(
+        &self,
+        writer: &mut PlatformLogWriter,
+        level: Level,
+        time: Option<SystemTime>,
+    ) -> fmt::Result {
+        if let Some(counters) = &self.tag_counters {
+            if let Some(count) = counters.next(writer.tag.to_bytes()) {
+                write!(writer, "#{count} ")?;
+            }
+        }
+        if self.level_icons {
+            writer.write_str(level_icon(level))?;
+            writer.write_char(' ')?;
+        }
+        if self.timestamp {
+            let time = time.unwrap_or_else(|| self.clock().system_time());
+            clock::write_timestamp(writer, time)?;
+            writer.write_char(' ')?;
+        }
+        if self.inter_log_delta {
+            clock::write_inter_log_delta(writer, self.clock().now())?;
+        }
+        if self.numeric_priority {
+            write!(writer, "{} ", writer.priority_value())?;
+        }
+        if let Some(profile) = self.profile {
+            write!(writer, "[{profile}] ")?;
+        }
+        if let Some(build_id) = &self.build_id {
+            write!(writer, "[build={build_id}] ")?;
+        }
+        if let Some(session) = &self.session {
+            write!(writer, "[session={session}] ")?;
+        }
+        if self.screen_name {
+            screen::write_prefix(writer)?;
+        }
+        if self.correlation_id {
+            correlation::write_prefix(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Disables all diagnostics of the logger itself.
+    ///
+    /// These are written straight to logcat under the `android_logger` tag,
+    /// without going through the `log` facade, filtering or formatting. This
+    /// includes the warning disabled by [`Config::quiet_init_failure()`].
+    pub fn silence_internal(mut self, silence: bool) -> Self {
+        self.silence_internal = silence;
+        self
+    }
+
+    /// Limits the number of log messages a single record may be split into.
+    ///
+    /// Records too long to fit into a single log message are split into
+    /// several ones. Once `max_chunks` messages were emitted for a record, the
+    /// rest of it is dropped, and the last emitted message ends with a
+    /// `…[truncated]` marker. This keeps e.g. `error!("{:#?}", huge_struct)`
+    /// from flooding logcat with hundreds of lines.
+    ///
+    /// A limit of `0` is treated as `1`.
+    pub fn max_chunks_per_record(mut self, max_chunks: usize) -> Self {
+        self.max_chunks = Some(max_chunks.max(1));
+        self
+    }
+
+    /// Stops formatting a record once `cap` bytes of it were written, dropping
+    /// the rest, and ends the last emitted message with a `…[truncated]`
+    /// marker.
+    ///
+    /// Unlike [`Config::max_chunks_per_record()`], which drops the output
+    /// past the limit but still formats the whole record, this aborts the
+    /// formatting itself: an accidental `{:#?}` of a huge graph, or a custom
+    /// format writing megabytes, costs no more than `cap` bytes of work. The
+    /// cap counts the prefixes (e.g. the timestamp) too, and a record of a
+    /// structured format cut short isn't valid anymore. With
+    /// [`Config::binary_encoding()`], messages are still fully formatted
+    /// before being encoded.
+    pub fn with_format_memory_cap(mut self, cap: usize) -> Self {
+        self.format_memory_cap = Some(cap);
+        self
+    }
+
+    /// Emits each record as a whole, while holding a process-wide lock.
+    ///
+    /// liblog is thread-safe, but only for individual messages: the messages
+    /// a long record is split into may be interleaved with the ones of
+    /// records logged concurrently by other threads. With this enabled, no
+    /// two records are emitted at the same time, which makes the output
+    /// ordering deterministic enough for tests asserting on it.
+    ///
+    /// This serializes all the logging threads of the process (of all the
+    /// loggers enabling it) on a single mutex, so it's off by default and
+    /// not recommended for production use.
+    pub fn serialize_output(mut self, serialize: bool) -> Self {
+        self.serialize_output = serialize;
+        self
+    }
+
+    /// Logs each record as a single log message, instead of splitting records
+    /// too long to fit into one.
+    ///
+    /// Whatever doesn't fit into a single message (about 4000 bytes) is lost,
+    /// without any marker. This takes precedence over
+    /// [`Config::max_chunks_per_record()`].
+    pub fn no_split(mut self, no_split: bool) -> Self {
+        self.no_split = no_split;
+        self
+    }
+
+    /// Drops records logged with an Android priority below the one of `level`,
+    /// before they are formatted.
+    ///
+    /// Unlike the maximum level, this applies to the priority records are
+    /// logged with, after mappings such as [`Config::trace_as_debug()`]: e.g.
+    /// with both `trace_as_debug(true)` and `min_priority(Level::Debug)`,
+    /// `trace!` records are still logged (as `DEBUG`). On Android, the floor
+    /// can also be set as an Android priority, with
+    /// `Config::min_android_priority()`.
+    pub fn min_priority(mut self, level: Level) -> Self {
+        self.min_priority = Some(priority_value(level));
+        self
+    }
+
+    /// Drops records logged with an Android priority below `priority`, like
+    /// [`Config::min_priority()`] does with the priority of a level.
+    ///
+    /// This allows floors without a level equivalent: no record is logged
+    /// with `FATAL` or `SILENT`, so both drop all of them.
     #[cfg(target_os = "android")]
-    pub fn new(buf_id: Option<LogId>, level: Level, tag: &CStr) -> PlatformLogWriter<'_> {
-        PlatformLogWriter::new_with_priority(
-            buf_id,
-            match level {
-                Level::Warn => LogPriority::WARN,
-                Level::Info => LogPriority::INFO,
-                Level::Debug => LogPriority::DEBUG,
-                Level::Error => LogPriority::ERROR,
-                Level::Trace => LogPriority::VERBOSE,
-            },
-            tag,
-        )
+    pub fn min_android_priority(mut self, priority: LogPriority) -> Self {
+        self.min_priority = Some(priority as i32);
+        self
     }
 
-    #[cfg(not(target_os = "android"))]
-    pub fn new(buf_id: Option<LogId>, level: Level, tag: &CStr) -> PlatformLogWriter<'_> {
-        #[allow(deprecated)] // created an issue #35 for this
-        PlatformLogWriter {
-            priority: level,
-            buf_id,
-            len: 0,
-            last_newline_index: 0,
-            tag,
-            buffer: uninit_array(),
+    /// Writes `tag` to `array` as a C string, truncated to the displayed
+    /// length (see [`Config::with_display_tag_len()`]).
+    fn fill_tag_bytes(&self, array: &mut [MaybeUninit<u8>], tag: &[u8]) {
+        let max_len = self.display_tag_len.unwrap_or(LOGGING_TAG_MAX_LEN);
+        if tag.len() > max_len {
+            for (input, output) in tag
+                .iter()
+                .take(max_len - 2)
+                .chain(b"..\0".iter())
+                .zip(array.iter_mut())
+            {
+                output.write(*input);
+            }
+        } else {
+            for (input, output) in tag.iter().chain(b"\0".iter()).zip(array.iter_mut()) {
+                output.write(*input);
+            }
         }
     }
 
-    /// Flush some bytes to android logger.
+    /// Returns the configured tag, or [`INTERNAL_TAG`], truncated like the
+    /// tags of records, for the messages of the logger itself.
+    fn internal_tag(&self) -> CString {
+        let tag = self.tag.as_deref().unwrap_or(INTERNAL_TAG);
+        let mut tag_bytes: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
+        self.fill_tag_bytes(&mut tag_bytes, tag.to_bytes());
+        unsafe { CStr::from_ptr(tag_bytes.as_ptr().cast()) }.to_owned()
+    }
+
+    /// Returns a writer of a message to `buf_id`, which also goes to the
+    /// other destinations of records: the [`Config::with_backend()`] backend,
+    /// the [`Config::tee_to_buffer()`] buffers and the
+    /// [`Config::with_socket()`] socket.
+    fn writer<'a>(
+        &'a self,
+        buf_id: Option<LogId>,
+        level: Level,
+        tag: &'a CStr,
+    ) -> PlatformLogWriter<'a> {
+        let mut writer = PlatformLogWriter::new(buf_id, level, tag);
+        writer.tee_buf_ids = &self.tee_buffers;
+        writer.backend = self.backend.as_deref();
+        #[cfg(unix)]
+        {
+            writer.socket = self.socket.as_ref();
+        }
+        writer
+    }
+
+    /// Returns a configuration only holding the destinations of `self` (see
+    /// [`Config::writer()`]), for messages written after the logger is gone.
+    fn destinations(&self) -> Config {
+        Config {
+            tee_buffers: self.tee_buffers.clone(),
+            backend: self.backend.clone(),
+            #[cfg(unix)]
+            socket: self.socket.clone(),
+            ..Config::default()
+        }
+    }
+
+    /// Returns the level whose Android priority is used for a record of
+    /// the given `level`.
+    fn level(&self, level: Level) -> Level {
+        match level {
+            Level::Trace if self.trace_as_debug => Level::Debug,
+            level => level,
+        }
+    }
+
+    fn filter_matches(&self, record: &Record) -> bool {
+        if let Some(ref filter) = self.filter {
+            filter.matches(record)
+        } else {
+            true
+        }
+    }
+
+    /// In debug builds, warns the first time a record is dropped because of
+    /// [`log::max_level()`], though the filter (see [`Config::with_filter()`])
+    /// allows it, e.g.:
     ///
-    /// If there is a newline, flush up to it.
-    /// If ther was no newline, flush all.
+    /// ```text
+    /// record at DEBUG dropped by max_level=INFO though filter permits it; call log::set_max_level higher
+    /// ```
     ///
-    /// Not guaranteed to flush everything.
-    fn temporal_flush(&mut self) {
+    /// Without [`Config::with_max_level()`], records are pre-filtered by the
+    /// maximum level of the `log` facade, which [`init_once()`] doesn't
+    /// change, so a filter allowing more verbose records than it seems to
+    /// silently not work. Only records passed to the logger can be explained:
+    /// the `log` macros drop them before, so this mostly helps with records
+    /// logged through functions of this crate, like [`log()`]. The warning is
+    /// written under [`INTERNAL_TAG`], unless [`Config::silence_internal()`]
+    /// is set, and nothing is checked in release builds.
+    pub fn explain_drops(mut self, enabled: bool) -> Self {
+        self.explain_drops = enabled;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: env_filter::Filter) -> Self {
+        self.filter = Some(filter);
+        self.directives = None;
+        self
+    }
+
+    /// Sets the maximum level of all modules in the filter.
+    ///
+    /// Unlike filters set with [`Config::with_filter()`], the directives set
+    /// with this, [`Config::filter_module()`] and [`Config::parse_filters()`]
+    /// are kept, and can be rendered back with [`Config::filter_directives()`].
+    /// They replace any filter set with [`Config::with_filter()`].
+    pub fn filter_level(self, level: LevelFilter) -> Self {
+        self.with_directives(|directives| directives.insert(None, level))
+    }
+
+    /// Sets the maximum level of `module` in the filter.
+    ///
+    /// See [`Config::filter_level()`].
+    pub fn filter_module(self, module: &str, level: LevelFilter) -> Self {
+        self.with_directives(|directives| directives.insert(Some(module), level))
+    }
+
+    /// Adds the directives of a `RUST_LOG`-style string (e.g.
+    /// `"info,my_crate=debug"`) to the filter, as [`FilterBuilder::parse()`]
+    /// does.
+    ///
+    /// See [`Config::filter_level()`].
+    pub fn parse_filters(self, filters: &str) -> Self {
+        self.with_directives(|directives| directives.parse(filters))
+    }
+
+    fn with_directives(mut self, update: impl FnOnce(&mut Directives)) -> Self {
+        let directives = self.directives.get_or_insert_with(Directives::default);
+        update(directives);
+        self.filter = Some(directives.build());
+        self
+    }
+
+    /// Renders the filter directives back to a `RUST_LOG`-style string, e.g.
+    /// to log the effective filter configuration.
+    ///
+    /// The string holds the level of all modules first, then the ones of each
+    /// module, and parses back to the same filter with
+    /// [`Config::parse_filters()`]. Returns `None` if the filter was not set
+    /// with directives (see [`Config::filter_level()`]).
+    pub fn filter_directives(&self) -> Option<String> {
+        self.directives.as_ref().map(Directives::to_string)
+    }
+
+    /// Builds the [`Filter`] this config applies, e.g. to share it with
+    /// another logger.
+    ///
+    /// This is a copy of the filter set with [`Config::with_filter()`], so its
+    /// [`Filter::filter()`] gives the maximum level the filter allows. Note,
+    /// that the maximum level set with [`Config::with_max_level()`] is applied
+    /// separately, and isn't included in the copy. If no filter is set, the
+    /// returned filter just allows records up to that maximum level (or any
+    /// record, if no maximum level is set either).
+    pub fn build_filter(&self) -> Filter {
+        match &self.filter {
+            Some(filter) => filter.clone(),
+            None => FilterBuilder::new()
+                .filter_level(self.log_level.unwrap_or(LevelFilter::Trace))
+                .build(),
+        }
+    }
+
+    /// Sets a callback deciding whether a record rejected by the filter
+    /// should be logged anyway.
+    ///
+    /// The callback runs only after the filter (see [`Config::with_filter()`])
+    /// rejected a record, so records passing the filter are not affected. If
+    /// it returns `true`, the record is logged as if it passed. This allows
+    /// e.g. sampling otherwise filtered out records:
+    ///
+    /// ```
+    /// # use android_logger::{Config, FilterBuilder};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// static FILTERED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// let config = Config::default()
+    ///     .with_filter(FilterBuilder::new().parse("info").build())
+    ///     // log 1 in 1000 of the filtered out records
+    ///     .on_filtered(|_| FILTERED.fetch_add(1, Ordering::Relaxed) % 1000 == 0);
+    /// ```
+    ///
+    /// Note, that records above the maximum level (see
+    /// [`Config::with_max_level()`]) are rejected before reaching the filter,
+    /// so the callback isn't run for them.
+    pub fn on_filtered<F>(mut self, on_filtered: F) -> Self
+    where
+        F: Fn(&Record) -> bool + Sync + Send + 'static,
+    {
+        self.on_filtered = Some(Arc::new(on_filtered));
+        self
+    }
+
+    fn resurrect_filtered(&self, record: &Record) -> bool {
+        self.on_filtered
+            .as_ref()
+            .is_some_and(|on_filtered| on_filtered(record))
+    }
+
+    pub fn with_tag<S: Into<Vec<u8>>>(mut self, tag: S) -> Self {
+        self.tag = Some(CString::new(tag).expect("Can't convert tag to CString"));
+        self
+    }
+
+    /// Logs the records of `level` under `tag`, whatever the tag would be
+    /// otherwise, e.g. to gather all errors under an `AppError` tag for
+    /// alerting.
+    ///
+    /// This can be called for several levels, and calling it again for a
+    /// level replaces its tag. As with [`Config::with_tag()`], the module
+    /// path is then added to the message, and tags are truncated to 23 bytes.
+    /// Only the tag given explicitly to a logging function (e.g.
+    /// [`log_if()`]) takes precedence over this.
+    pub fn with_level_tag_override(mut self, level: Level, tag: &str) -> Self {
+        self.level_tags.retain(|&(other, _)| other != level);
+        self.level_tags.push((level, tag.to_owned()));
+        self
+    }
+
+    fn level_tag(&self, level: Level) -> Option<&str> {
+        self.level_tags
+            .iter()
+            .find(|&&(other, _)| other == level)
+            .map(|(_, tag)| tag.as_str())
+    }
+
+    /// Sets the subsystem of the app logging (e.g. `com.example.sync`), which
+    /// the tag is made of along with the category (see
+    /// [`Config::with_category()`]), as `subsystem:category`.
+    ///
+    /// This allows a finer categorization than a single tag, like the
+    /// subsystems and categories of Apple's `os_log`. liblog has no such
+    /// fields, so they are always encoded into the tag: if the tag would be
+    /// too long, the subsystem is shortened so that the category is kept
+    /// whole. The tag made of both replaces the one set with
+    /// [`Config::with_tag()`], and the module path is added to messages as
+    /// with a custom tag.
+    pub fn with_subsystem(mut self, subsystem: &str) -> Self {
+        self.subsystem = Some(subsystem.to_owned());
+        self
+    }
+
+    /// Sets the category of the logs (e.g. `network`), which the tag is made
+    /// of along with the subsystem, see [`Config::with_subsystem()`].
+    ///
+    /// Without a subsystem, the category alone is the tag.
+    pub fn with_category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_owned());
+        self
+    }
+
+    /// Returns the tag made of the subsystem and category, if any is set.
+    fn subsystem_tag(&self) -> Option<String> {
+        let max_len = self.display_tag_len.unwrap_or(LOGGING_TAG_MAX_LEN);
+        match (&self.subsystem, &self.category) {
+            (None, None) => None,
+            (Some(part), None) | (None, Some(part)) => Some(part.clone()),
+            (Some(subsystem), Some(category)) => {
+                let mut keep = subsystem.len();
+                if keep + 1 + category.len() > max_len && category.len() + 2 <= max_len {
+                    keep = max_len - category.len() - 1;
+                    while !subsystem.is_char_boundary(keep) {
+                        keep -= 1;
+                    }
+                }
+                Some(format!("{}:{category}", &subsystem[..keep]))
+            }
+        }
+    }
+
+    /// Logs messages with an empty tag, so that logcat shows little more than
+    /// the message itself.
+    ///
+    /// liblog requires a tag, but accepts an empty one. This overrides the tag
+    /// set with [`Config::with_tag()`], and the module path is not added to
+    /// messages then. Tags passed explicitly (e.g. to [`timed()`]) are still
+    /// used.
+    pub fn without_tag(mut self, without_tag: bool) -> Self {
+        self.without_tag = without_tag;
+        self
+    }
+
+    /// Uses just the crate name (the first segment of the module path) as the
+    /// tag, instead of the full module path, when no tag is set with
+    /// [`Config::with_tag()`].
+    ///
+    /// So logs from `my_crate::deep::nested` appear under the `my_crate` tag,
+    /// grouping all logs of a crate under a single, short tag.
+    pub fn tag_from_crate(mut self, enabled: bool) -> Self {
+        self.tag_from_crate = enabled;
+        self
+    }
+
+    /// Prefixes messages with the module path they were logged from, e.g.
+    /// `my_crate::net: connected`, whatever their tag.
+    ///
+    /// By default, the module path is only added with a custom tag (see
+    /// [`Config::with_tag()`]), as it is the tag otherwise. With tags only
+    /// derived from it (e.g. [`Config::tag_from_crate()`]), or passed
+    /// explicitly (e.g. to [`timed()`]), this keeps logs traceable to the
+    /// exact module. Records without a module path are left as is. This only
+    /// applies to the plain format.
+    pub fn always_include_module_path(mut self, enabled: bool) -> Self {
+        self.always_include_module_path = enabled;
+        self
+    }
+
+    /// Returns the tag to use for a record from `module_path`, if no custom
+    /// tag is set.
+    fn module_tag<'a>(&self, module_path: &'a str) -> Cow<'a, str> {
+        if self.tag_from_crate {
+            Cow::Borrowed(module_path.split("::").next().unwrap_or_default())
+        } else if let Some(format) = &self.tag_abbrev_format {
+            let max_len = self.display_tag_len.unwrap_or(LOGGING_TAG_MAX_LEN);
+            Cow::Owned(format.abbreviate(module_path, max_len))
+        } else {
+            Cow::Borrowed(module_path)
+        }
+    }
+
+    /// Warns once, under [`INTERNAL_TAG`], for each tag which distinct modules
+    /// are logged under, e.g. because their long module paths are truncated
+    /// to the same tag.
+    ///
+    /// Such tags make filtering logcat by tag ambiguous. This only checks the
+    /// tags resolved from module paths, i.e. when no tag is set with
+    /// [`Config::with_tag()`], and only the first 1024 distinct tags. The
+    /// warning is disabled by [`Config::silence_internal()`].
+    pub fn warn_tag_collisions(mut self, enabled: bool) -> Self {
+        self.tag_collisions = enabled.then(TagCollisions::default);
+        self
+    }
+
+    /// Renders the module paths used as tags (when no tag is set with
+    /// [`Config::with_tag()`]) with the segments joined by
+    /// `format.separator`, abbreviating them to fit into a tag.
+    ///
+    /// While the tag is too long, the leading segments are abbreviated, one
+    /// after the other, to their first character followed by
+    /// `format.ellipsis`. The last segment is always kept whole, and tags
+    /// still too long are truncated as usual. With `/` and `…`,
+    /// `my_crate::network::http::client` is logged under `m…/n…/http/client`.
+    ///
+    /// This has no effect with [`Config::tag_from_crate()`].
+    pub fn with_tag_abbrev_format(mut self, format: AbbrevFormat) -> Self {
+        self.tag_abbrev_format = Some(format);
+        self
+    }
+
+    /// Prefixes messages with the time elapsed since the previous record,
+    /// e.g. `(+12ms) `, to spot the gaps between logs, e.g. latency spikes.
+    ///
+    /// The previous record is the last one logged with this option, by any
+    /// logger and thread of the process: with concurrent threads, the gap is
+    /// the one to the record of another thread, and is only approximate as
+    /// they race. It comes after the timestamp (see
+    /// [`Config::with_timestamp()`]) and only applies to the plain format.
+    /// Nothing is added to the first record.
+    pub fn with_inter_log_delta(mut self, enabled: bool) -> Self {
+        self.inter_log_delta = enabled;
+        self
+    }
+
+    /// Sets the format function for formatting the log output.
+    ///
+    /// If formatting a record fails (i.e. the format function, or a
+    /// `Display` or `Debug` impl it calls, returns an error), whatever was
+    /// written so far is still logged, followed by a `[format error]` marker.
+    /// The same applies to records logged without a format function.
+    ///
+    /// ```
+    /// # use android_logger::Config;
+    /// android_logger::init_once(
+    ///     Config::default()
+    ///         .with_max_level(log::LevelFilter::Trace)
+    ///         .format(|f, record| write!(f, "my_app: {}", record.args()))
+    /// )
+    /// ```
+    pub fn format<F>(mut self, format: F) -> Self
+    where
+        F: Fn(&mut dyn fmt::Write, &Record) -> fmt::Result + Sync + Send + 'static,
+    {
+        self.custom_format = Some(Arc::new(format));
+        self.output_format = OutputFormat::Plain;
+        self
+    }
+
+    /// Registers the format `format` under `name`, to switch to at runtime
+    /// with [`set_active_format()`].
+    ///
+    /// Until then, records are formatted as configured otherwise (the
+    /// `default` preset). A format registered under the name of a built-in
+    /// preset (`default`, `json` or `logfmt`) replaces it, and registering a
+    /// name again replaces its format.
+    pub fn with_format_preset<F>(mut self, name: impl Into<String>, format: F) -> Self
+    where
+        F: Fn(&mut dyn fmt::Write, &Record) -> fmt::Result + Sync + Send + 'static,
+    {
+        let name = name.into();
+        let format: FormatFn = Arc::new(format);
+        match self
+            .format_presets
+            .iter_mut()
+            .find(|(preset, _)| *preset == name)
+        {
+            Some((_, existing)) => *existing = format,
+            None => self.format_presets.push((name, format)),
+        }
+        self
+    }
+
+    /// Adds a fixed set of key-values (e.g. the app version or device model)
+    /// to every record in structured formats (e.g. [`Config::format_json()`]).
+    ///
+    /// They are output before the record's own key-values (with the `kv`
+    /// feature). If a record has a key-value with the same key as a base one,
+    /// the record's value wins and the base one is omitted.
+    pub fn with_base_kv(mut self, base_kv: Vec<(String, String)>) -> Self {
+        self.base_kv = base_kv;
+        self
+    }
+
+    /// Adds the profile `android_logger` was built with, `debug` or `release`
+    /// (according to `debug_assertions`), to every record, so that logs of
+    /// both kinds of builds can be told apart in a combined capture.
+    ///
+    /// The profile of `android_logger` is usually the one of the app, but a
+    /// profile may override the settings of some packages. To use the
+    /// profile of the calling crate instead, use [`with_profile!`]. In the
+    /// plain format, messages are prefixed with `[debug] ` or `[release] `,
+    /// before the one of [`Config::with_build_id()`]. In structured formats
+    /// (e.g. [`Config::format_json()`]), it is a `profile` key-value output
+    /// first.
+    pub fn with_profile_marker(mut self, enabled: bool) -> Self {
+        self.profile = enabled.then(|| profile::name(cfg!(debug_assertions)));
+        self
+    }
+
+    /// Adds the profile `debug` (or `release` if `debug` is false) to every
+    /// record, as [`Config::with_profile_marker()`] does.
+    ///
+    /// This is meant to pass the profile of the calling crate, see
+    /// [`with_profile!`].
+    pub fn with_build_profile(mut self, debug: bool) -> Self {
+        self.profile = Some(profile::name(debug));
+        self
+    }
+
+    /// Adds the identifier of the build (e.g. its git commit hash) to every
+    /// record, so logs from the field can be matched with the exact build
+    /// which emitted them.
+    ///
+    /// It can't be detected by the logger, so it must be passed in, e.g. as
+    /// `env!("GIT_HASH")` set by a build script. In the plain format, messages
+    /// are prefixed with `[build=<id>] `, after the prefixes enabled with e.g.
+    /// [`Config::with_timestamp()`]. In structured formats (e.g.
+    /// [`Config::format_json()`]), it is a `build` key-value output before the
+    /// base ones (see [`Config::with_base_kv()`]).
+    pub fn with_build_id(mut self, build_id: impl Into<String>) -> Self {
+        self.build_id = Some(build_id.into());
+        self
+    }
+
+    /// Adds a random session id, e.g. `3fa85f64`, to every record, so that
+    /// the logs of a single launch of the app can be told apart from the
+    /// others in a combined capture.
+    ///
+    /// The id is generated when the logger is created, so it changes with
+    /// every process (and every logger). In the plain format, messages are
+    /// prefixed with `[session=<id>] ` after the build id (see
+    /// [`Config::with_build_id()`]). In structured formats, it is a `session`
+    /// key-value output after the build id.
+    pub fn with_session_id(mut self, enabled: bool) -> Self {
+        self.session_id = enabled;
+        self
+    }
+
+    /// Returns the key-values added to every record in structured formats:
+    /// the build id, the session id, then the base key-values.
+    fn base_kv(&self) -> impl Iterator<Item = (&str, &str)> {
+        let profile = self.profile.map(|profile| ("profile", profile));
+        let build_id = self.build_id.as_deref().map(|id| ("build", id));
+        let session = self.session.as_deref().map(|id| ("session", id));
+        profile.into_iter().chain(build_id).chain(session).chain(
+            self.base_kv
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+    }
+
+    /// Adds the version of the schema of the structured formats (e.g.
+    /// [`Config::format_json()`]) to every record, as their first field
+    /// (`"v":1` in JSON, `v=1` in logfmt).
+    ///
+    /// Log pipelines can then tell apart the records of different versions
+    /// of an app, when the fields it logs change. By default, no version is
+    /// output. It is up to the app to bump it when its records change.
+    pub fn with_schema_version(mut self, version: u32) -> Self {
+        self.schema_version = Some(version);
+        self
+    }
+
+    /// Formats each record as a single-line JSON object holding its level,
+    /// target and message, e.g.:
+    ///
+    /// ```text
+    /// {"level":"INFO","target":"my_app::net","msg":"connected"}
+    /// ```
+    ///
+    /// With the `kv` feature, the record's key-values are added as further
+    /// string members. Key-values named `v`, `level`, `target`, `msg` or
+    /// `repeat` are prefixed with `kv_` (e.g. `kv_level`), so that they
+    /// don't duplicate those fields. Strings are escaped while being
+    /// written, so this doesn't allocate.
+    ///
+    /// Records longer than a logcat message (about 4000 bytes) are still
+    /// split into several messages, none of which is a valid JSON object on
+    /// its own.
+    ///
+    /// This replaces any format set with [`Config::format()`].
+    pub fn format_json(mut self) -> Self {
+        self.custom_format = None;
+        self.output_format = OutputFormat::Json;
+        self
+    }
+
+    /// Formats each record as a logfmt line holding its level, target and
+    /// message, e.g.:
+    ///
+    /// ```text
+    /// level=info target=my_app::net msg="connected to host"
+    /// ```
+    ///
+    /// The base key-values (see [`Config::with_base_kv()`]) and, with the
+    /// `kv` feature, the record's key-values are added as further pairs.
+    /// Values are quoted if they're empty or hold spaces, `=`, `"`, `\` or
+    /// control characters, and the message is always quoted. Only key-values
+    /// which aren't strings are formatted into an intermediate buffer. As
+    /// with [`Config::format_json()`], key-values named after a field are
+    /// prefixed with `kv_`, and long records are split into several
+    /// messages.
+    ///
+    /// This replaces any format set with [`Config::format()`].
+    pub fn format_logfmt(mut self) -> Self {
+        self.custom_format = None;
+        self.output_format = OutputFormat::Logfmt;
+        self
+    }
+
+    /// Logs a machine-readable header describing the output format when
+    /// [`init_once()`] installs the logger, before any record, so that
+    /// log-viewing tools can configure their parsing from it, e.g.:
+    ///
+    /// ```text
+    /// #android_logger format=logfmt schema=1 tag=app
+    /// ```
+    ///
+    /// The header is `#android_logger` followed by space-separated
+    /// `key=value` pairs, in this order:
+    ///
+    /// - `format`, always: `plain`, `json`, `logfmt` or `custom` (see
+    ///   [`Config::format()`]);
+    /// - `schema`, the [`Config::with_schema_version()`], if set;
+    /// - `tag`, the [`Config::with_tag()`], if set, quoted and escaped as a
+    ///   logfmt value if needed.
+    ///
+    /// Further keys may be added in the future, so parsers should ignore the
+    /// ones they don't know. The header is logged at the info level, under
+    /// the configured tag (or [`INTERNAL_TAG`]).
+    pub fn emit_tooling_header(mut self, enabled: bool) -> Self {
+        self.tooling_header = enabled;
+        self
+    }
+}
+
+pub struct PlatformLogWriter<'a> {
+    #[cfg(target_os = "android")]
+    priority: LogPriority,
+    #[cfg(not(target_os = "android"))]
+    priority: Level,
+    #[cfg(target_os = "android")]
+    buf_id: Option<log_ffi::log_id_t>,
+    #[cfg(not(target_os = "android"))]
+    buf_id: Option<LogId>,
+    len: usize,
+    last_newline_index: usize,
+    tag: &'a CStr,
+    buffer: [MaybeUninit<u8>; LOGGING_MSG_MAX_LEN + 1],
+    /// Number of chunks output so far.
+    chunks: usize,
+    /// Maximum number of chunks to output, before dropping the rest.
+    max_chunks: usize,
+    /// Whether the maximum number of chunks was reached.
+    truncated: bool,
+    /// Appended to the last chunk output, if the rest is dropped.
+    truncation_marker: &'static [u8],
+    /// Buffers each chunk is also output to, in order, after `buf_id`.
+    tee_buf_ids: &'a [LogId],
+    /// Whether a newline ending the output is dropped.
+    trim_trailing_newline: bool,
+    /// Destination of the output, instead of the Android logging system.
+    backend: Option<&'a dyn LogBackend>,
+    /// Whether chunks made of whitespace only are dropped.
+    skip_whitespace_only_chunks: bool,
+    /// Maximum length of a single chunk, at most [`LOGGING_MSG_MAX_LEN`].
+    max_len: usize,
+    /// Maximum number of bytes written for a record, before dropping the
+    /// rest.
+    max_bytes: usize,
+    /// Number of bytes written since the last flush.
+    written: usize,
+    /// Socket each chunk is also written to, after the buffers.
+    #[cfg(unix)]
+    socket: Option<&'a SocketTee>,
+}
+
+/// Marker appended to a message whose formatting failed.
+const FORMAT_ERROR_MARKER: &str = "[format error]";
+
+/// Marker appended to the last chunk output, if the rest is dropped.
+const TRUNCATION_MARKER: &[u8] = "…[truncated]".as_bytes();
+
+impl<'a> PlatformLogWriter<'a> {
+    #[cfg(target_os = "android")]
+    pub fn new_with_priority(
+        buf_id: Option<LogId>,
+        priority: log_ffi::LogPriority,
+        tag: &CStr,
+    ) -> PlatformLogWriter<'_> {
+        #[allow(deprecated)] // created an issue #35 for this
+        PlatformLogWriter {
+            priority,
+            buf_id: LogId::to_native(buf_id),
+            len: 0,
+            last_newline_index: 0,
+            tag,
+            buffer: uninit_array(),
+            chunks: 0,
+            max_chunks: usize::MAX,
+            truncated: false,
+            truncation_marker: TRUNCATION_MARKER,
+            tee_buf_ids: &[],
+            trim_trailing_newline: false,
+            backend: None,
+            skip_whitespace_only_chunks: false,
+            max_len: LOGGING_MSG_MAX_LEN,
+            max_bytes: usize::MAX,
+            written: 0,
+            #[cfg(unix)]
+            socket: None,
+        }
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn new(buf_id: Option<LogId>, level: Level, tag: &CStr) -> PlatformLogWriter<'_> {
+        PlatformLogWriter::new_with_priority(buf_id, level_to_priority(level), tag)
+    }
+
+    #[cfg(not(target_os = "android"))]
+    pub fn new(buf_id: Option<LogId>, level: Level, tag: &CStr) -> PlatformLogWriter<'_> {
+        #[allow(deprecated)] // created an issue #35 for this
+        PlatformLogWriter {
+            priority: level,
+            buf_id,
+            len: 0,
+            last_newline_index: 0,
+            tag,
+            buffer: uninit_array(),
+            chunks: 0,
+            max_chunks: usize::MAX,
+            truncated: false,
+            truncation_marker: TRUNCATION_MARKER,
+            tee_buf_ids: &[],
+            trim_trailing_newline: false,
+            backend: None,
+            skip_whitespace_only_chunks: false,
+            max_len: LOGGING_MSG_MAX_LEN,
+            max_bytes: usize::MAX,
+            written: 0,
+            #[cfg(unix)]
+            socket: None,
+        }
+    }
+
+    /// Numeric value of the Android priority this writer logs with.
+    fn priority_value(&self) -> i32 {
+        #[cfg(target_os = "android")]
+        {
+            self.priority as i32
+        }
+        #[cfg(not(target_os = "android"))]
+        priority_value(self.priority)
+    }
+
+    /// Flush some bytes to android logger.
+    ///
+    /// If there is a newline, flush up to it.
+    /// If ther was no newline, flush all.
+    ///
+    /// Not guaranteed to flush everything.
+    fn temporal_flush(&mut self) {
         let total_len = self.len;
 
-        if total_len == 0 {
-            return;
+        if total_len == 0 {
+            return;
+        }
+
+        if self.chunks + 1 >= self.max_chunks {
+            self.output_truncated();
+            return;
+        }
+
+        if self.last_newline_index > 0 {
+            let copy_from_index = self.last_newline_index;
+            let remaining_chunk_len = total_len - copy_from_index;
+
+            self.output_specified_len(copy_from_index);
+            self.copy_bytes_to_start(copy_from_index, remaining_chunk_len);
+            self.len = remaining_chunk_len;
+        } else {
+            self.output_specified_len(total_len);
+            self.len = 0;
+        }
+        self.last_newline_index = 0;
+    }
+
+    /// Flush everything remaining to android logger.
+    pub fn flush(&mut self) {
+        self.written = 0;
+        let mut total_len = self.len;
+
+        if self.trim_trailing_newline
+            && total_len > 0
+            && unsafe { self.buffer[total_len - 1].assume_init() } == b'\n'
+        {
+            total_len -= 1;
+            self.len = total_len;
+        }
+
+        if total_len == 0 {
+            return;
+        }
+
+        self.output_specified_len(total_len);
+        self.len = 0;
+        self.last_newline_index = 0;
+    }
+
+    /// Output everything as the last chunk, marked as truncated, and drop
+    /// anything written afterwards.
+    fn output_truncated(&mut self) {
+        let marker = self.truncation_marker;
+        let len = self.len.min(self.max_len.saturating_sub(marker.len()));
+        for (output, input) in self.buffer[len..].iter_mut().zip(marker) {
+            output.write(*input);
+        }
+        self.output_specified_len(len + marker.len());
+        self.len = 0;
+        self.last_newline_index = 0;
+        self.truncated = true;
+    }
+
+    /// Output buffer up until the \0 which will be placed at `len` position.
+    fn output_specified_len(&mut self, len: usize) {
+        if self.skip_whitespace_only_chunks
+            && self.buffer[..len]
+                .iter()
+                .all(|byte| unsafe { byte.assume_init() }.is_ascii_whitespace())
+        {
+            return;
+        }
+
+        self.chunks += 1;
+
+        let mut last_byte = MaybeUninit::new(b'\0');
+
+        mem::swap(&mut last_byte, unsafe {
+            self.buffer.get_unchecked_mut(len)
+        });
+
+        let msg: &CStr = unsafe { CStr::from_ptr(self.buffer.as_ptr().cast()) };
+        self.write_to(self.buf_id, msg);
+        for &tee_buf_id in self.tee_buf_ids {
+            #[cfg(target_os = "android")]
+            let tee_buf_id = LogId::to_native(Some(tee_buf_id));
+            #[cfg(not(target_os = "android"))]
+            let tee_buf_id = Some(tee_buf_id);
+            self.write_to(tee_buf_id, msg);
+        }
+        // The socket copies what's written above, so it's gated the same.
+        #[cfg(unix)]
+        if let Some(socket) = self.socket {
+            if !logging_denied() && !capture::is_capturing() {
+                socket.write(self.priority_value(), self.tag, msg);
+            }
+        }
+
+        unsafe { *self.buffer.get_unchecked_mut(len) = last_byte };
+    }
+
+    /// Writes `msg` to `buf_id`, through the backend if any.
+    #[cfg(target_os = "android")]
+    fn write_to(&self, buf_id: Option<log_ffi::log_id_t>, msg: &CStr) {
+        match self.backend {
+            Some(backend) => backend::write(
+                backend,
+                buf_id.map(LogId::from_native),
+                closest_level(self.priority),
+                self.tag,
+                msg,
+            ),
+            None => android_log(buf_id, self.priority, self.tag, msg),
+        }
+    }
+
+    /// Writes `msg` to `buf_id`, through the backend if any.
+    #[cfg(not(target_os = "android"))]
+    fn write_to(&self, buf_id: Option<LogId>, msg: &CStr) {
+        match self.backend {
+            Some(backend) => backend::write(backend, buf_id, self.priority, self.tag, msg),
+            None => android_log(buf_id, self.priority, self.tag, msg),
+        }
+    }
+
+    /// Copy `len` bytes from `index` position to starting position.
+    fn copy_bytes_to_start(&mut self, index: usize, len: usize) {
+        let dst = self.buffer.as_mut_ptr();
+        let src = unsafe { self.buffer.as_ptr().add(index) };
+        unsafe { ptr::copy(src, dst, len) };
+    }
+}
+
+impl<'a> fmt::Write for PlatformLogWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Ok(());
+        }
+
+        // Past the cap, keep what fits (up to a char boundary), and stop the
+        // formatting once it's output.
+        let remaining = self.max_bytes - self.written;
+        let capped = s.len() > remaining;
+        let s = if capped {
+            let mut end = remaining;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            &s[..end]
+        } else {
+            s
+        };
+        self.written += s.len();
+
+        let mut incomming_bytes = s.as_bytes();
+
+        while !incomming_bytes.is_empty() {
+            let len = self.len;
+
+            // write everything possible to buffer and mark last \n
+            let new_len = len + incomming_bytes.len();
+            let last_newline = self.buffer[len..self.max_len]
+                .iter_mut()
+                .zip(incomming_bytes)
+                .enumerate()
+                .fold(None, |acc, (i, (output, input))| {
+                    output.write(*input);
+                    if *input == b'\n' {
+                        Some(i)
+                    } else {
+                        acc
+                    }
+                });
+
+            // update last \n index
+            if let Some(newline) = last_newline {
+                self.last_newline_index = len + newline;
+            }
+
+            // calculate how many bytes were written
+            let written_len = if new_len <= self.max_len {
+                // if the len was not exceeded
+                self.len = new_len;
+                new_len - len // written len
+            } else {
+                // if new length was exceeded
+                self.len = self.max_len;
+                self.temporal_flush();
+                if self.truncated {
+                    return Ok(());
+                }
+
+                self.max_len - len // written len
+            };
+
+            incomming_bytes = &incomming_bytes[written_len..];
+        }
+
+        if capped {
+            self.output_truncated();
+            return Err(fmt::Error);
+        }
+
+        Ok(())
+    }
+}
+
+/// Send a log record to Android logging backend.
+///
+/// This action does not require initialization. However, without initialization it
+/// will use the default filter, which allows all logs, unless buffered with
+/// [`enable_early_buffering()`].
+pub fn log(record: &Record) {
+    if early::try_buffer(record) {
+        return;
+    }
+
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .log(record)
+}
+
+/// Writes `msg` to the Android logging system as is, bypassing the logger.
+///
+/// This is the lowest-overhead way to log an already formatted message: no
+/// filtering, formatting or splitting is done, and no logger needs to be
+/// initialized. The caller is responsible for keeping `msg` within the
+/// liblog message length limit (about 4000 bytes), as longer messages are
+/// truncated by liblog, and for `tag` not exceeding 23 bytes.
+pub fn write_cstr(buf_id: Option<LogId>, level: Level, tag: &CStr, msg: &CStr) {
+    #[cfg(target_os = "android")]
+    android_log(LogId::to_native(buf_id), level_to_priority(level), tag, msg);
+    #[cfg(not(target_os = "android"))]
+    android_log(buf_id, level, tag, msg);
+}
+
+/// Initializes the global logger with an android logger.
+///
+/// This can be called many times, but will only initialize logging once,
+/// and will not replace any other previously initialized logger.
+///
+/// It is ok to call this at the activity creation, and it will be
+/// repeatedly called on every lifecycle restart (i.e. screen rotation).
+///
+/// Unless disabled with [`Config::quiet_init_failure()`], a warning is written
+/// directly to logcat (under the `android_logger` tag) if the logger could not
+/// be installed because another logger is installed instead. Repeated calls,
+/// where the android logger is already installed, stay silent.
+pub fn init_once(config: Config) {
+    let log_level = config.log_level;
+    let quiet_init_failure = config.quiet_init_failure || config.silence_internal;
+    let manage_max_level = !config.unmanaged_max_level;
+    let logger = ANDROID_LOGGER.get_or_init(|| {
+        let logger = AndroidLogger::new(config);
+        logger.log_tooling_header();
+        logger
+    });
+    early::replay(logger);
+
+    if let Err(err) = log::set_logger(logger) {
+        if !quiet_init_failure {
+            warn_not_installed(logger, log::logger(), &err);
+        }
+    } else {
+        #[cfg(target_os = "android")]
+        let log_level = properties::apply_tag_properties_on_init(logger).or(log_level);
+        if let Some(level) = log_level.filter(|_| manage_max_level) {
+            log::set_max_level(level);
+        }
+    }
+}
+
+/// Warns that `logger` could not be installed, unless it's the `installed`
+/// logger already.
+fn warn_not_installed(logger: &AndroidLogger, installed: &dyn Log, err: &dyn fmt::Display) {
+    if !ptr::addr_eq(installed, logger) {
+        write_internal(
+            Level::Warn,
+            format_args!("not installed, log::set_logger failed: {err}"),
+        );
+    }
+}
+
+/// Tag of the diagnostics of the logger itself, e.g. the warning written when
+/// [`init_once()`] fails to install the logger.
+///
+/// These can be shown on their own with `adb logcat -s android_logger`, or
+/// hidden with `adb logcat android_logger:S`. It is also the tag of the
+/// heartbeat ([`Config::with_heartbeat()`]) when no tag is set.
+pub const INTERNAL_TAG: &CStr = c"android_logger";
+
+/// Writes a diagnostic message of the logger itself straight to the main
+/// buffer under [`INTERNAL_TAG`], bypassing the `log` facade and any
+/// configuration.
+///
+/// As the facade is bypassed, this is safe to call while logging a record.
+/// Callers are responsible for honoring [`Config::silence_internal()`].
+fn write_internal(level: Level, args: fmt::Arguments) {
+    let mut writer = PlatformLogWriter::new(None, level, INTERNAL_TAG);
+    let _ = fmt::write(&mut writer, args);
+    writer.flush();
+}
+
+/// Flushes the global logger when dropped.
+///
+/// Rust has no reliable exit hook covering every way a process can end, so
+/// there is nothing the logger can do on its own to drain records still held
+/// in memory when the process goes away. Holding a `FlushGuard` for the
+/// lifetime of `main` (or of the native activity) makes sure [`Log::flush()`]
+/// is called on the installed logger when the guard goes out of scope, which
+/// also covers unwinding out of a panic.
+///
+/// ```
+/// android_logger::init_once(android_logger::Config::default());
+/// let _guard = android_logger::FlushGuard::new();
+///
+/// // ..
+/// ```
+///
+/// Note, that the guard cannot run if the process is terminated abruptly
+/// (e.g. via [`std::process::exit()`] or a signal).
+#[must_use = "the logger is flushed when the guard is dropped"]
+pub struct FlushGuard {
+    logger: &'static dyn Log,
+}
+
+impl FlushGuard {
+    /// Create a guard flushing the logger installed via [`log::set_logger()`].
+    pub fn new() -> FlushGuard {
+        FlushGuard {
+            logger: log::logger(),
+        }
+    }
+}
+
+impl Default for FlushGuard {
+    fn default() -> FlushGuard {
+        FlushGuard::new()
+    }
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        self.logger.flush();
+    }
+}
+
+// FIXME: When `maybe_uninit_uninit_array ` is stabilized, use it instead of this helper
+fn uninit_array<const N: usize, T>() -> [MaybeUninit<T>; N] {
+    // SAFETY: Array contains MaybeUninit, which is fine to be uninit
+    unsafe { MaybeUninit::uninit().assume_init() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::fmt::Write;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A single call to `android_log`.
+    #[derive(Clone, Debug)]
+    pub(crate) struct Emitted {
+        pub(crate) buf_id: Option<LogId>,
+        pub(crate) priority: Level,
+        pub(crate) tag: String,
+        pub(crate) msg: String,
+    }
+
+    thread_local! {
+        static EMITTED: RefCell<Vec<Emitted>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Calls to `android_log` from all threads, in the order they happened.
+    static ALL_EMITTED: Mutex<Vec<Emitted>> = Mutex::new(Vec::new());
+
+    pub(crate) fn capture(buf_id: Option<LogId>, priority: Level, tag: &CStr, msg: &CStr) {
+        let emitted = Emitted {
+            buf_id,
+            priority,
+            tag: tag.to_string_lossy().into_owned(),
+            msg: msg.to_string_lossy().into_owned(),
+        };
+        ALL_EMITTED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(emitted.clone());
+        let _ = EMITTED.try_with(|emitted_here| emitted_here.borrow_mut().push(emitted));
+    }
+
+    #[cfg(target_os = "android")]
+    pub(crate) fn capture_native(
+        buf_id: Option<log_ffi::log_id_t>,
+        prio: log_ffi::LogPriority,
+        tag: &CStr,
+        msg: &CStr,
+    ) {
+        capture(
+            buf_id.map(LogId::from_native),
+            closest_level(prio),
+            tag,
+            msg,
+        );
+    }
+
+    /// Returns everything emitted on the current thread since the last call.
+    pub(crate) fn take_emitted() -> Vec<Emitted> {
+        EMITTED.with_borrow_mut(mem::take)
+    }
+
+    /// Takes the calls to `android_log` made by any thread with `tag`.
+    pub(crate) fn take_all_emitted(tag: &str) -> Vec<Emitted> {
+        let mut all = ALL_EMITTED.lock().unwrap_or_else(PoisonError::into_inner);
+        let (taken, kept) = mem::take(&mut *all).into_iter().partition(|e| e.tag == tag);
+        *all = kept;
+        taken
+    }
+
+    /// Logs `records` with a logger of `config`, and returns everything it
+    /// emitted.
+    pub(crate) fn log_emitted(config: Config, records: &[Record]) -> Vec<Emitted> {
+        let logger = AndroidLogger::new(config);
+        for record in records {
+            logger.log(record);
+        }
+        take_emitted()
+    }
+
+    /// Logs `records` with a logger of `config`, and returns the messages it
+    /// emitted.
+    pub(crate) fn log_msgs(config: Config, records: &[Record]) -> Vec<String> {
+        log_emitted(config, records)
+            .into_iter()
+            .map(|e| e.msg)
+            .collect()
+    }
+
+    #[test]
+    fn check_config_values() {
+        // Filter is checked in config_filter_match below.
+        let config = Config::default()
+            .with_max_level(LevelFilter::Trace)
+            .with_log_buffer(LogId::System)
+            .with_tag("my_app");
+
+        assert_eq!(config.log_level, Some(LevelFilter::Trace));
+        assert_eq!(config.buf_id, Some(LogId::System));
+        assert_eq!(config.tag, Some(CString::new("my_app").unwrap()));
+    }
+
+    #[test]
+    fn parse_log_id() {
+        assert_eq!("main".parse(), Ok(LogId::Main));
+        assert_eq!("Crash".parse(), Ok(LogId::Crash));
+        assert_eq!("STATS".parse(), Ok(LogId::Stats));
+        assert_eq!("nope".parse::<LogId>(), Err(ParseLogIdError(())));
+    }
+
+    #[test]
+    fn module_tag() {
+        let config = Config::default();
+        assert_eq!(
+            config.module_tag("my_crate::deep::nested"),
+            "my_crate::deep::nested"
+        );
+
+        let config = Config::default().tag_from_crate(true);
+        assert_eq!(config.module_tag("my_crate::deep::nested"), "my_crate");
+        assert_eq!(config.module_tag("my_crate"), "my_crate");
+        assert_eq!(config.module_tag(""), "");
+    }
+
+    #[test]
+    fn module_path_is_always_included() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .tag_from_crate(true)
+                .always_include_module_path(true),
+        );
+
+        for module_path in [Some("my_crate::deep::nested"), None] {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .module_path(module_path)
+                    .args(format_args!("msg"))
+                    .build(),
+            );
+        }
+
+        let emitted = take_emitted();
+        assert_eq!(emitted[0].tag, "my_crate");
+        assert_eq!(emitted[0].msg, "my_crate::deep::nested: msg");
+        assert_eq!(emitted[1].msg, "msg");
+    }
+
+    #[test]
+    fn subsystem_and_category_tag() {
+        let tag = |config: Config| config.subsystem_tag();
+
+        assert_eq!(tag(Config::default()), None);
+        assert_eq!(
+            tag(Config::default().with_subsystem("com.example")),
+            Some("com.example".to_owned())
+        );
+        assert_eq!(
+            tag(Config::default().with_category("net")),
+            Some("net".to_owned())
+        );
+        assert_eq!(
+            tag(Config::default()
+                .with_category("net")
+                .with_subsystem("com.example")),
+            Some("com.example:net".to_owned())
+        );
+        // The subsystem is shortened to fit into 23 bytes.
+        assert_eq!(
+            tag(Config::default()
+                .with_subsystem("com.example.very.long.app")
+                .with_category("network")),
+            Some("com.example.ver:network".to_owned())
+        );
+        // Unless the category alone is too long.
+        assert_eq!(
+            tag(Config::default()
+                .with_subsystem("app")
+                .with_category("a_category_much_too_long")),
+            Some("app:a_category_much_too_long".to_owned())
+        );
+    }
+
+    #[test]
+    fn subsystem_tag_is_logged() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("replaced")
+                .with_subsystem("com.example")
+                .with_category("net"),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .module_path(Some("app::net"))
+                .args(format_args!("connected"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted[0].tag, "com.example:net");
+        assert_eq!(emitted[0].msg, "app::net: connected");
+    }
+
+    #[test]
+    fn abbreviated_module_tag() {
+        let config = Config::default().with_tag_abbrev_format(AbbrevFormat {
+            separator: "/",
+            ellipsis: "…",
+        });
+        assert_eq!(
+            config.module_tag("my_crate::network::http::client"),
+            "m…/n…/http/client"
+        );
+        assert_eq!(config.module_tag("my_crate::net"), "my_crate/net");
+        assert_eq!(
+            config.module_tag("a_very_long_crate_name::the_longest_module_name"),
+            "a…/the_longest_module_name"
+        );
+        assert_eq!(config.module_tag(""), "");
+
+        let config = Config::default()
+            .with_display_tag_len(8)
+            .with_tag_abbrev_format(AbbrevFormat {
+                separator: ".",
+                ellipsis: "",
+            });
+        assert_eq!(config.module_tag("my_crate::net::http"), "m.n.http");
+    }
+
+    #[test]
+    fn tag_from_crate_uses_first_segment() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .tag_from_crate(true),
+        );
+
+        for module_path in [Some("my_crate::deep::nested"), Some("other"), None] {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .module_path(module_path)
+                    .args(format_args!("msg"))
+                    .build(),
+            );
+        }
+
+        let tags: Vec<String> = take_emitted().into_iter().map(|e| e.tag).collect();
+        assert_eq!(tags, ["my_crate", "other", ""]);
+    }
+
+    #[test]
+    fn main_buffer_is_accessible() {
+        assert!(accessible_buffers().contains(&LogId::Main));
+    }
+
+    #[test]
+    fn debuggable_sets_max_level() {
+        assert_eq!(
+            Config::default().with_debuggable(true).log_level,
+            Some(LevelFilter::Debug)
+        );
+        assert_eq!(
+            Config::default().with_debuggable(false).log_level,
+            Some(LevelFilter::Info)
+        );
+    }
+
+    #[test]
+    fn log_calls_formatter() {
+        static FORMAT_FN_WAS_CALLED: AtomicBool = AtomicBool::new(false);
+        let config = Config::default()
+            .with_max_level(LevelFilter::Info)
+            .format(|_, _| {
+                FORMAT_FN_WAS_CALLED.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+        let logger = AndroidLogger::new(config);
+
+        logger.log(&Record::builder().level(Level::Info).build());
+
+        assert!(FORMAT_FN_WAS_CALLED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn trace_uses_verbose_priority_by_default() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Trace));
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Trace)
+                .args(format_args!("trace"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].priority, Level::Trace);
+        assert_eq!(emitted[0].msg, "trace");
+    }
+
+    #[test]
+    fn trace_as_debug_uses_debug_priority() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Trace)
+                .trace_as_debug(true),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Trace)
+                .args(format_args!("trace"))
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("info"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0].priority, Level::Debug);
+        assert_eq!(emitted[0].msg, "trace");
+        assert_eq!(emitted[1].priority, Level::Info);
+    }
+
+    #[test]
+    fn logger_enabled_threshold() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        assert!(logger.enabled(&log::MetadataBuilder::new().level(Level::Warn).build()));
+        assert!(logger.enabled(&log::MetadataBuilder::new().level(Level::Info).build()));
+        assert!(!logger.enabled(&log::MetadataBuilder::new().level(Level::Debug).build()));
+    }
+
+    // Test whether the filter gets called correctly. Not meant to be exhaustive for all filter
+    // options, as these are handled directly by the filter itself.
+    #[test]
+    fn config_filter_match() {
+        let info_record = Record::builder().level(Level::Info).build();
+        let debug_record = Record::builder().level(Level::Debug).build();
+
+        let info_all_filter = env_filter::Builder::new().parse("info").build();
+        let info_all_config = Config::default().with_filter(info_all_filter);
+
+        assert!(info_all_config.filter_matches(&info_record));
+        assert!(!info_all_config.filter_matches(&debug_record));
+    }
+
+    #[test]
+    fn on_filtered_resurrects_sampled_records() {
+        static FILTERED: AtomicUsize = AtomicUsize::new(0);
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Trace)
+                .with_filter(env_filter::Builder::new().parse("info").build())
+                .on_filtered(|record| {
+                    assert_eq!(record.level(), Level::Debug);
+                    FILTERED.fetch_add(1, Ordering::SeqCst) % 4 == 0
+                }),
+        );
+
+        for i in 0..8 {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Debug)
+                    .args(format_args!("debug {i}"))
+                    .build(),
+            );
+        }
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("info"))
+                .build(),
+        );
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["debug 0", "debug 4", "info"]);
+        assert_eq!(FILTERED.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn numeric_priority_prefix() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Trace)
+                .with_numeric_priority(true),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Error)
+                .args(format_args!("boom"))
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .level(Level::Trace)
+                .args(format_args!("details"))
+                .build(),
+        );
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["6 boom", "2 details"]);
+    }
+
+    #[test]
+    fn failed_init_writes_marker() {
+        struct Other;
+
+        impl Log for Other {
+            fn enabled(&self, _: &Metadata) -> bool {
+                false
+            }
+
+            fn log(&self, _: &Record) {}
+
+            fn flush(&self) {}
+        }
+
+        let logger = AndroidLogger::new(Config::default());
+        warn_not_installed(&logger, &Other, &"already set");
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].tag, INTERNAL_TAG.to_str().unwrap());
+        assert_eq!(emitted[0].priority, Level::Warn);
+        assert_eq!(
+            emitted[0].msg,
+            "not installed, log::set_logger failed: already set"
+        );
+
+        // The android logger being installed already isn't a failure.
+        warn_not_installed(&logger, &logger, &"already set");
+        assert!(take_emitted().is_empty());
+    }
+
+    #[test]
+    fn build_filter_from_directives() {
+        let config = Config::default().with_filter(
+            env_filter::Builder::new()
+                .parse("info,my_crate=debug")
+                .build(),
+        );
+
+        let filter = config.build_filter();
+
+        assert_eq!(filter.filter(), LevelFilter::Debug);
+        assert!(filter.matches(
+            &Record::builder()
+                .level(Level::Debug)
+                .target("my_crate::net")
+                .build()
+        ));
+        assert!(!filter.matches(
+            &Record::builder()
+                .level(Level::Debug)
+                .target("other")
+                .build()
+        ));
+        // The config keeps its own filter.
+        assert!(config.filter.is_some());
+    }
+
+    #[test]
+    fn filter_directives_round_trip() {
+        let config = Config::default()
+            .filter_level(LevelFilter::Info)
+            .filter_module("my_crate", LevelFilter::Debug);
+
+        let rendered = config.filter_directives().unwrap();
+        assert_eq!(rendered, "info,my_crate=debug");
+        assert_eq!(config.build_filter().filter(), LevelFilter::Debug);
+
+        let parsed = Config::default().parse_filters(&rendered);
+        assert_eq!(parsed.filter_directives().unwrap(), rendered);
+
+        let config = config.with_filter(FilterBuilder::new().build());
+        assert_eq!(config.filter_directives(), None);
+    }
+
+    #[test]
+    fn build_filter_without_directives() {
+        let config = Config::default().with_max_level(LevelFilter::Warn);
+        assert_eq!(config.build_filter().filter(), LevelFilter::Warn);
+
+        let config = Config::default();
+        assert_eq!(config.build_filter().filter(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn fill_tag_bytes_truncates_long_tag() {
+        let logger = AndroidLogger::new(Config::default());
+        let too_long_tag: [u8; LOGGING_TAG_MAX_LEN + 20] = [b'a'; LOGGING_TAG_MAX_LEN + 20];
+
+        let mut result: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
+        logger.fill_tag_bytes(&mut result, &too_long_tag);
+
+        let mut expected_result = [b'a'; LOGGING_TAG_MAX_LEN - 2].to_vec();
+        expected_result.extend("..\0".as_bytes());
+        assert_eq!(unsafe { assume_init_slice(&result) }, expected_result);
+    }
+
+    #[test]
+    fn fill_tag_bytes_truncates_to_display_tag_len() {
+        let logger = AndroidLogger::new(Config::default().with_display_tag_len(10));
+        let long_tag = [b'a'; 50];
+
+        let mut result: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
+        logger.fill_tag_bytes(&mut result, &long_tag);
+
+        assert_eq!(unsafe { assume_init_slice(&result[..11]) }, b"aaaaaaaa..\0");
+    }
+
+    #[test]
+    #[should_panic(expected = "display tag length")]
+    fn display_tag_len_must_fit_ellipsis() {
+        let _ = Config::default().with_display_tag_len(2);
+    }
+
+    #[test]
+    fn fill_tag_bytes_keeps_short_tag() {
+        let logger = AndroidLogger::new(Config::default());
+        let short_tag: [u8; 3] = [b'a'; 3];
+
+        let mut result: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
+        logger.fill_tag_bytes(&mut result, &short_tag);
+
+        let mut expected_result = short_tag.to_vec();
+        expected_result.push(0);
+        assert_eq!(unsafe { assume_init_slice(&result[..4]) }, expected_result);
+    }
+
+    #[test]
+    fn platform_log_writer_init_values() {
+        let tag = c"tag";
+
+        let writer = PlatformLogWriter::new(None, Level::Warn, tag);
+
+        assert_eq!(writer.tag, tag);
+        // Android uses LogPriority instead, which doesn't implement equality checks
+        #[cfg(not(target_os = "android"))]
+        assert_eq!(writer.priority, Level::Warn);
+    }
+
+    #[test]
+    fn max_chunks_per_record_truncates() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .max_chunks_per_record(3),
+        );
+        let msg = "a".repeat(50000);
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{msg}"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 3);
+        assert_eq!(emitted[0].msg.len(), LOGGING_MSG_MAX_LEN);
+        assert_eq!(emitted[1].msg.len(), LOGGING_MSG_MAX_LEN);
+        assert_eq!(emitted[2].msg.len(), LOGGING_MSG_MAX_LEN);
+        assert!(emitted[2].msg.ends_with("a…[truncated]"));
+    }
+
+    #[test]
+    fn format_memory_cap_aborts_formatting() {
+        struct Huge(Arc<AtomicUsize>);
+
+        impl fmt::Debug for Huge {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for _ in 0..10000 {
+                    self.0.fetch_add(1, Ordering::Relaxed);
+                    f.write_str("node é ")?;
+                }
+                Ok(())
+            }
+        }
+
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_format_memory_cap(100),
+        );
+        let nodes = Arc::new(AtomicUsize::new(0));
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{:?}", Huge(nodes.clone())))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            emitted[0].msg,
+            format!("{}node…[truncated]", "node é ".repeat(12))
+        );
+        assert_eq!(nodes.load(Ordering::Relaxed), 13);
+    }
+
+    #[test]
+    fn build_id_is_added_to_records() {
+        let log = |config: Config| {
+            let logger = AndroidLogger::new(
+                config
+                    .with_max_level(LevelFilter::Info)
+                    .with_build_id("3f2a9c1"),
+            );
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("app")
+                    .args(format_args!("msg"))
+                    .build(),
+            );
+            take_emitted().remove(0).msg
+        };
+
+        assert_eq!(
+            log(Config::default().with_numeric_priority(true)),
+            "4 [build=3f2a9c1] msg"
+        );
+        assert_eq!(
+            log(Config::default()
+                .with_base_kv(vec![("app".to_owned(), "1.2.3".to_owned())])
+                .format_json()),
+            r#"{"level":"INFO","target":"app","msg":"msg","build":"3f2a9c1","app":"1.2.3"}"#
+        );
+    }
+
+    #[test]
+    fn whitespace_only_chunks_are_skipped() {
+        let log = |skip| {
+            let logger = AndroidLogger::new(
+                Config::default()
+                    .with_max_level(LevelFilter::Info)
+                    .skip_whitespace_only_chunks(skip),
+            );
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!(
+                        "{}\n  \t",
+                        "a".repeat(LOGGING_MSG_MAX_LEN - 1)
+                    ))
+                    .build(),
+            );
+            take_emitted()
+                .into_iter()
+                .map(|e| e.msg)
+                .collect::<Vec<_>>()
+        };
+
+        let emitted = log(false);
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[1], "\n  \t");
+
+        let emitted = log(true);
+        assert_eq!(emitted, ["a".repeat(LOGGING_MSG_MAX_LEN - 1)]);
+    }
+
+    #[test]
+    fn total_payload_len_shrinks_chunks() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("a_rather_long_tag_20")
+                .with_total_payload_len(100),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{}", "a".repeat(200)))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        // 100 - 20 bytes of tag - 3 bytes of overhead, the first chunk also
+        // holds the ": " prefix.
+        let lens: Vec<usize> = emitted.iter().map(|e| e.msg.len()).collect();
+        assert_eq!(lens, [77, 77, 48]);
+        assert!(emitted.iter().all(|e| e.tag.len() == 20));
+    }
+
+    #[test]
+    fn mirror_gets_accepted_records() {
+        #[derive(Clone, Default)]
+        struct Mirror(Arc<Mutex<Vec<(Level, String)>>>);
+
+        impl Log for Mirror {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &Record) {
+                let mut records = self.0.lock().unwrap();
+                records.push((record.level(), record.args().to_string()));
+            }
+
+            fn flush(&self) {}
         }
 
-        if self.last_newline_index > 0 {
-            let copy_from_index = self.last_newline_index;
-            let remaining_chunk_len = total_len - copy_from_index;
+        let mirror = Mirror::default();
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .mirror_to(mirror.clone()),
+        );
 
-            self.output_specified_len(copy_from_index);
-            self.copy_bytes_to_start(copy_from_index, remaining_chunk_len);
-            self.len = remaining_chunk_len;
-        } else {
-            self.output_specified_len(total_len);
-            self.len = 0;
+        for (level, msg) in [(Level::Warn, "forwarded"), (Level::Debug, "filtered")] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .args(format_args!("{msg}"))
+                    .build(),
+            );
         }
-        self.last_newline_index = 0;
+
+        assert_eq!(
+            *mirror.0.lock().unwrap(),
+            [(Level::Warn, "forwarded".to_owned())]
+        );
+        assert_eq!(take_emitted().len(), 1);
+
+        // Records suppressed by the logger itself are still mirrored.
+        let mirror = Mirror::default();
+        let repeated = || test::record(Level::Info, "app", None, format_args!("repeated"));
+        let emitted = log_emitted(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_rate_limit_per_tag(2, Duration::from_secs(60))
+                .dedup_window(Duration::from_secs(60), 16)
+                .mirror_to(mirror.clone()),
+            &[repeated(), repeated(), repeated()],
+        );
+
+        // Deduped, then rate limited.
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(mirror.0.lock().unwrap().len(), 3);
     }
 
-    /// Flush everything remaining to android logger.
-    pub fn flush(&mut self) {
-        let total_len = self.len;
+    #[test]
+    fn min_priority_drops_records_before_formatting() {
+        static FORMATTED: AtomicUsize = AtomicUsize::new(0);
 
-        if total_len == 0 {
-            return;
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Trace)
+                .min_priority(Level::Info)
+                .format(|f, record| {
+                    FORMATTED.fetch_add(1, Ordering::SeqCst);
+                    write!(f, "{}", record.args())
+                }),
+        );
+
+        for level in [Level::Debug, Level::Warn] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .args(format_args!("msg"))
+                    .build(),
+            );
         }
 
-        self.output_specified_len(total_len);
-        self.len = 0;
-        self.last_newline_index = 0;
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].priority, Level::Warn);
+        assert_eq!(FORMATTED.load(Ordering::SeqCst), 1);
     }
 
-    /// Output buffer up until the \0 which will be placed at `len` position.
-    fn output_specified_len(&mut self, len: usize) {
-        let mut last_byte = MaybeUninit::new(b'\0');
+    #[cfg(target_os = "android")]
+    #[test]
+    fn priority_values_match_android_priorities() {
+        for level in [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ] {
+            assert_eq!(priority_value(level), level_to_priority(level) as i32);
+        }
+    }
 
-        mem::swap(&mut last_byte, unsafe {
-            self.buffer.get_unchecked_mut(len)
-        });
+    #[test]
+    fn min_priority_applies_after_mapping() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Trace)
+                .trace_as_debug(true)
+                .min_priority(Level::Debug),
+        );
 
-        let msg: &CStr = unsafe { CStr::from_ptr(self.buffer.as_ptr().cast()) };
-        android_log(self.buf_id, self.priority, self.tag, msg);
+        logger.log(
+            &Record::builder()
+                .level(Level::Trace)
+                .args(format_args!("msg"))
+                .build(),
+        );
 
-        unsafe { *self.buffer.get_unchecked_mut(len) = last_byte };
+        assert_eq!(take_emitted().len(), 1);
     }
 
-    /// Copy `len` bytes from `index` position to starting position.
-    fn copy_bytes_to_start(&mut self, index: usize, len: usize) {
-        let dst = self.buffer.as_mut_ptr();
-        let src = unsafe { self.buffer.as_ptr().add(index) };
-        unsafe { ptr::copy(src, dst, len) };
-    }
-}
+    #[test]
+    fn trailing_newline_is_trimmed() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .trim_trailing_newline(true),
+        );
 
-impl<'a> fmt::Write for PlatformLogWriter<'a> {
-    fn write_str(&mut self, s: &str) -> fmt::Result {
-        let mut incomming_bytes = s.as_bytes();
+        for msg in ["line\n", "two\nlines\n\n", "\n"] {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("{msg}"))
+                    .build(),
+            );
+        }
 
-        while !incomming_bytes.is_empty() {
-            let len = self.len;
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["line", "two\nlines\n"]);
+    }
 
-            // write everything possible to buffer and mark last \n
-            let new_len = len + incomming_bytes.len();
-            let last_newline = self.buffer[len..LOGGING_MSG_MAX_LEN]
-                .iter_mut()
-                .zip(incomming_bytes)
-                .enumerate()
-                .fold(None, |acc, (i, (output, input))| {
-                    output.write(*input);
-                    if *input == b'\n' {
-                        Some(i)
-                    } else {
-                        acc
-                    }
-                });
+    #[test]
+    fn without_tag_emits_empty_tag() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("ignored")
+                .without_tag(true),
+        );
 
-            // update last \n index
-            if let Some(newline) = last_newline {
-                self.last_newline_index = len + newline;
-            }
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .module_path(Some("app::net"))
+                .args(format_args!("just the message"))
+                .build(),
+        );
 
-            // calculate how many bytes were written
-            let written_len = if new_len <= LOGGING_MSG_MAX_LEN {
-                // if the len was not exceeded
-                self.len = new_len;
-                new_len - len // written len
-            } else {
-                // if new length was exceeded
-                self.len = LOGGING_MSG_MAX_LEN;
-                self.temporal_flush();
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].tag, "");
+        assert_eq!(emitted[0].msg, "just the message");
+    }
 
-                LOGGING_MSG_MAX_LEN - len // written len
-            };
+    #[test]
+    fn level_tag_override_applies_to_its_level_only() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_level_tag_override(Level::Error, "AppError"),
+        );
 
-            incomming_bytes = &incomming_bytes[written_len..];
+        for level in [Level::Error, Level::Info] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .module_path(Some("app::net"))
+                    .args(format_args!("msg"))
+                    .build(),
+            );
         }
 
-        Ok(())
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0].tag, "AppError");
+        assert_eq!(emitted[0].msg, "app::net: msg");
+        assert_eq!(emitted[1].tag, "app::net");
+        assert_eq!(emitted[1].msg, "msg");
     }
-}
-
-/// Send a log record to Android logging backend.
-///
-/// This action does not require initialization. However, without initialization it
-/// will use the default filter, which allows all logs.
-pub fn log(record: &Record) {
-    ANDROID_LOGGER
-        .get_or_init(AndroidLogger::default)
-        .log(record)
-}
 
-/// Initializes the global logger with an android logger.
-///
-/// This can be called many times, but will only initialize logging once,
-/// and will not replace any other previously initialized logger.
-///
-/// It is ok to call this at the activity creation, and it will be
-/// repeatedly called on every lifecycle restart (i.e. screen rotation).
-pub fn init_once(config: Config) {
-    let log_level = config.log_level;
-    let logger = ANDROID_LOGGER.get_or_init(|| AndroidLogger::new(config));
+    #[test]
+    fn cloned_configs_make_identical_loggers() {
+        let base = Config::default()
+            .with_max_level(LevelFilter::Trace)
+            .with_filter(FilterBuilder::new().parse("info,app::net=debug").build())
+            .dedup_window(Duration::from_secs(60), 8);
+        let record = |level, target| test::record(level, target, Some(target), format_args!("msg"));
+        let records = [
+            record(Level::Debug, "app::net"),
+            record(Level::Debug, "app::ui"),
+            record(Level::Info, "app::ui"),
+            record(Level::Info, "app::ui"),
+        ];
 
-    if let Err(err) = log::set_logger(logger) {
-        log::debug!("android_logger: log::set_logger failed: {}", err);
-    } else if let Some(level) = log_level {
-        log::set_max_level(level);
+        let emitted: Vec<_> = [LogId::Main, LogId::Crash]
+            .into_iter()
+            .flat_map(|buf_id| log_emitted(base.clone().with_log_buffer(buf_id), &records))
+            .map(|e| (e.buf_id, e.tag))
+            .collect();
+        assert_eq!(
+            emitted,
+            [
+                (Some(LogId::Main), "app::net".to_owned()),
+                (Some(LogId::Main), "app::ui".to_owned()),
+                (Some(LogId::Crash), "app::net".to_owned()),
+                (Some(LogId::Crash), "app::ui".to_owned()),
+            ]
+        );
     }
-}
 
-// FIXME: When `maybe_uninit_uninit_array ` is stabilized, use it instead of this helper
-fn uninit_array<const N: usize, T>() -> [MaybeUninit<T>; N] {
-    // SAFETY: Array contains MaybeUninit, which is fine to be uninit
-    unsafe { MaybeUninit::uninit().assume_init() }
-}
+    #[test]
+    fn tee_writes_buffers_in_order() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_log_buffer(LogId::Main)
+                .tee_to_buffer(LogId::Crash),
+        );
+        let msg = "a".repeat(LOGGING_MSG_MAX_LEN + 1);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fmt::Write;
-    use std::sync::atomic::{AtomicBool, Ordering};
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{msg}"))
+                .build(),
+        );
+
+        let emitted: Vec<(Option<LogId>, usize)> = take_emitted()
+            .into_iter()
+            .map(|e| (e.buf_id, e.msg.len()))
+            .collect();
+        assert_eq!(
+            emitted,
+            [
+                (Some(LogId::Main), LOGGING_MSG_MAX_LEN),
+                (Some(LogId::Crash), LOGGING_MSG_MAX_LEN),
+                (Some(LogId::Main), 1),
+                (Some(LogId::Crash), 1),
+            ]
+        );
+    }
 
     #[test]
-    fn check_config_values() {
-        // Filter is checked in config_filter_match below.
+    fn modules_are_routed_to_buffers() {
         let config = Config::default()
-            .with_max_level(LevelFilter::Trace)
             .with_log_buffer(LogId::System)
-            .with_tag("my_app");
+            .route_module_to_buffer("app::net", LogId::Radio)
+            .route_module_to_buffer("app", LogId::Main)
+            .route_module_to_buffer("app::net::crash", LogId::Crash);
 
-        assert_eq!(config.log_level, Some(LevelFilter::Trace));
-        assert_eq!(config.buf_id, Some(LogId::System));
-        assert_eq!(config.tag, Some(CString::new("my_app").unwrap()));
+        assert_eq!(config.buffer_for("app"), Some(LogId::Main));
+        assert_eq!(config.buffer_for("app::ui"), Some(LogId::Main));
+        assert_eq!(config.buffer_for("app::net"), Some(LogId::Radio));
+        assert_eq!(config.buffer_for("app::net::http"), Some(LogId::Radio));
+        assert_eq!(config.buffer_for("app::net::crash"), Some(LogId::Crash));
+        assert_eq!(config.buffer_for("application"), Some(LogId::System));
+        assert_eq!(config.buffer_for("other"), Some(LogId::System));
+
+        assert_eq!(Config::default().buffer_for("app"), None);
     }
 
     #[test]
-    fn log_calls_formatter() {
-        static FORMAT_FN_WAS_CALLED: AtomicBool = AtomicBool::new(false);
-        let config = Config::default()
-            .with_max_level(LevelFilter::Info)
-            .format(|_, _| {
-                FORMAT_FN_WAS_CALLED.store(true, Ordering::SeqCst);
-                Ok(())
-            });
-        let logger = AndroidLogger::new(config);
+    fn routed_module_is_logged_to_its_buffer() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .route_module_to_buffer("app::radio", LogId::Radio),
+        );
 
-        logger.log(&Record::builder().level(Level::Info).build());
+        for target in ["app::radio::modem", "app::ui"] {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .target(target)
+                    .args(format_args!("msg"))
+                    .build(),
+            );
+        }
 
-        assert!(FORMAT_FN_WAS_CALLED.load(Ordering::SeqCst));
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0].buf_id, Some(LogId::Radio));
+        assert_eq!(emitted[1].buf_id, None);
     }
 
     #[test]
-    fn logger_enabled_threshold() {
-        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+    fn blocked_targets_are_dropped() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .block_targets(vec!["noisy".to_owned(), "app::chatty".to_owned()]),
+        );
 
-        assert!(logger.enabled(&log::MetadataBuilder::new().level(Level::Warn).build()));
-        assert!(logger.enabled(&log::MetadataBuilder::new().level(Level::Info).build()));
-        assert!(!logger.enabled(&log::MetadataBuilder::new().level(Level::Debug).build()));
+        for target in ["noisy", "noisy::io", "noisydep", "app::chatty::x", "app"] {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Error)
+                    .target(target)
+                    .args(format_args!("{target}"))
+                    .build(),
+            );
+        }
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["noisydep", "app"]);
     }
 
-    // Test whether the filter gets called correctly. Not meant to be exhaustive for all filter
-    // options, as these are handled directly by the filter itself.
     #[test]
-    fn config_filter_match() {
-        let info_record = Record::builder().level(Level::Info).build();
-        let debug_record = Record::builder().level(Level::Debug).build();
+    fn level_icon_prefixes_message() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_level_icons(true)
+                .with_numeric_priority(true),
+        );
 
-        let info_all_filter = env_filter::Builder::new().parse("info").build();
-        let info_all_config = Config::default().with_filter(info_all_filter);
+        logger.log(
+            &Record::builder()
+                .level(Level::Error)
+                .args(format_args!("boom"))
+                .build(),
+        );
 
-        assert!(info_all_config.filter_matches(&info_record));
-        assert!(!info_all_config.filter_matches(&debug_record));
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].msg, "❌ 6 boom");
     }
 
     #[test]
-    fn fill_tag_bytes_truncates_long_tag() {
-        let logger = AndroidLogger::new(Config::default());
-        let too_long_tag: [u8; LOGGING_TAG_MAX_LEN + 20] = [b'a'; LOGGING_TAG_MAX_LEN + 20];
+    fn no_split_emits_single_message() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .no_split(true),
+        );
+        let msg = "line\n".repeat(2000);
 
-        let mut result: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
-        logger.fill_tag_bytes(&mut result, &too_long_tag);
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{msg}"))
+                .build(),
+        );
 
-        let mut expected_result = [b'a'; LOGGING_TAG_MAX_LEN - 2].to_vec();
-        expected_result.extend("..\0".as_bytes());
-        assert_eq!(unsafe { assume_init_slice(&result) }, expected_result);
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].msg, msg[..LOGGING_MSG_MAX_LEN]);
     }
 
     #[test]
-    fn fill_tag_bytes_keeps_short_tag() {
-        let logger = AndroidLogger::new(Config::default());
-        let short_tag: [u8; 3] = [b'a'; 3];
+    fn max_chunks_per_record_keeps_short_records() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .max_chunks_per_record(2),
+        );
+        let msg = "a".repeat(LOGGING_MSG_MAX_LEN + 10);
 
-        let mut result: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
-        logger.fill_tag_bytes(&mut result, &short_tag);
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("{msg}"))
+                .build(),
+        );
 
-        let mut expected_result = short_tag.to_vec();
-        expected_result.push(0);
-        assert_eq!(unsafe { assume_init_slice(&result[..4]) }, expected_result);
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[1].msg, "a".repeat(10));
     }
 
+    #[cfg(feature = "kmsg")]
     #[test]
-    fn platform_log_writer_init_values() {
-        let tag = CStr::from_bytes_with_nul(b"tag\0").unwrap();
+    fn kmsg_prefix_only_for_kernel_buffer() {
+        for (buf_id, expected) in [
+            (LogId::Kernel, "<4>kernel thing"),
+            (LogId::Main, "kernel thing"),
+        ] {
+            let logger = AndroidLogger::new(
+                Config::default()
+                    .with_max_level(LevelFilter::Info)
+                    .with_log_buffer(buf_id),
+            );
 
-        let writer = PlatformLogWriter::new(None, Level::Warn, tag);
+            logger.log(
+                &Record::builder()
+                    .level(Level::Warn)
+                    .args(format_args!("kernel thing"))
+                    .build(),
+            );
 
-        assert_eq!(writer.tag, tag);
-        // Android uses LogPriority instead, which doesn't implement equality checks
-        #[cfg(not(target_os = "android"))]
-        assert_eq!(writer.priority, Level::Warn);
+            let emitted = take_emitted();
+            assert_eq!(emitted.len(), 1);
+            assert_eq!(emitted[0].msg, expected);
+        }
+    }
+
+    #[test]
+    fn format_error_is_marked() {
+        struct Failing;
+
+        impl fmt::Display for Failing {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("partial")?;
+                Err(fmt::Error)
+            }
+        }
+
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("value: {} and more", Failing))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].msg, "value: partial[format error]");
+    }
+
+    #[test]
+    fn write_cstr_is_not_split_or_formatted() {
+        let msg = CString::new(format!("  {{}}\n{}", "a".repeat(LOGGING_MSG_MAX_LEN))).unwrap();
+
+        write_cstr(Some(LogId::Crash), Level::Error, c"raw", &msg);
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].buf_id, Some(LogId::Crash));
+        assert_eq!(emitted[0].priority, Level::Error);
+        assert_eq!(emitted[0].tag, "raw");
+        assert_eq!(emitted[0].msg.as_bytes(), msg.as_bytes());
+    }
+
+    #[test]
+    fn serialized_output_does_not_interleave_records() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("serialized")
+                .serialize_output(true),
+        );
+
+        std::thread::scope(|scope| {
+            for thread in ['a', 'b', 'c', 'd'] {
+                let logger = &logger;
+                scope.spawn(move || {
+                    // Each record is split into 3 messages.
+                    let msg = thread.to_string().repeat(2 * LOGGING_MSG_MAX_LEN + 10);
+                    for _ in 0..20 {
+                        logger.log(
+                            &Record::builder()
+                                .level(Level::Info)
+                                .args(format_args!("{msg}"))
+                                .build(),
+                        );
+                    }
+                });
+            }
+        });
+
+        let emitted = take_all_emitted("serialized");
+        assert_eq!(emitted.len(), 4 * 20 * 3);
+        for record in emitted.chunks(3) {
+            let thread = record[0].msg.chars().last().unwrap();
+            assert!(record.iter().all(|e| e.msg.ends_with(thread)));
+        }
     }
 
     #[test]
@@ -736,12 +4167,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn flush_guard_flushes_on_drop() {
+        static FLUSHED: AtomicBool = AtomicBool::new(false);
+
+        struct FlushTracker;
+
+        impl Log for FlushTracker {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, _: &Record) {}
+
+            fn flush(&self) {
+                FLUSHED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let guard = FlushGuard {
+            logger: &FlushTracker,
+        };
+        assert!(!FLUSHED.load(Ordering::SeqCst));
+
+        drop(guard);
+
+        assert!(FLUSHED.load(Ordering::SeqCst));
+    }
+
     fn get_tag_writer() -> PlatformLogWriter<'static> {
-        PlatformLogWriter::new(
-            None,
-            Level::Warn,
-            CStr::from_bytes_with_nul(b"tag\0").unwrap(),
-        )
+        PlatformLogWriter::new(None, Level::Warn, c"tag")
     }
 
     unsafe fn assume_init_slice<T>(slice: &[MaybeUninit<T>]) -> &[T] {
@@ -0,0 +1,102 @@
+//! Markers of the start and end of the threads logging.
+
+use super::*;
+use std::cell::RefCell;
+use std::thread;
+
+/// Logs the end marker of the current thread when dropped, that is when the
+/// thread-local storage of the thread is destroyed.
+struct ThreadGuard {
+    destinations: Config,
+    buf_id: Option<LogId>,
+    level: Level,
+    tag: CString,
+    thread: String,
+}
+
+impl Drop for ThreadGuard {
+    fn drop(&mut self) {
+        let mut writer = self.destinations.writer(self.buf_id, self.level, &self.tag);
+        let _ = write!(writer, "thread {} ended", self.thread);
+        writer.flush();
+    }
+}
+
+thread_local! {
+    /// Guard of the current thread, set on its first log.
+    static GUARD: RefCell<Option<ThreadGuard>> = const { RefCell::new(None) };
+}
+
+/// Logs the start marker of the current thread, unless it was already logged,
+/// and registers the guard logging its end marker.
+pub(crate) fn register(config: &Config, buf_id: Option<LogId>, level: Level, tag: &CStr) {
+    let _ = GUARD.try_with(|guard| {
+        let Ok(mut guard) = guard.try_borrow_mut() else {
+            return;
+        };
+        if guard.is_some() {
+            return;
+        }
+
+        let current = thread::current();
+        let thread = match current.name() {
+            Some(name) => format!("'{name}'"),
+            None => format!("{:?}", current.id()),
+        };
+
+        let mut writer = config.writer(buf_id, level, tag);
+        let _ = write!(writer, "thread {thread} started");
+        writer.flush();
+
+        *guard = Some(ThreadGuard {
+            destinations: config.destinations(),
+            buf_id,
+            level,
+            tag: tag.to_owned(),
+            thread,
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_all_emitted;
+
+    #[test]
+    fn thread_start_and_end_markers() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("lifecycle")
+                .log_thread_lifecycle(true),
+        );
+
+        thread::Builder::new()
+            .name("worker".to_owned())
+            .spawn(move || {
+                for msg in ["first", "second"] {
+                    logger.log(
+                        &Record::builder()
+                            .level(Level::Info)
+                            .args(format_args!("{msg}"))
+                            .build(),
+                    );
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        let emitted: Vec<String> = take_all_emitted("lifecycle")
+            .into_iter()
+            .map(|e| e.msg)
+            .collect();
+        assert_eq!(
+            emitted[..3],
+            ["thread 'worker' started", ": first", ": second"]
+        );
+        // The end marker is best-effort, but is logged on this platform.
+        assert_eq!(emitted[3..], ["thread 'worker' ended"]);
+    }
+}
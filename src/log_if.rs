@@ -0,0 +1,82 @@
+//! Conditional logging, with lazily built messages.
+
+use super::*;
+
+impl AndroidLogger {
+    /// Logs the message built by `msg` if `cond` holds, see [`log_if()`].
+    pub fn log_if<F, M>(&self, cond: bool, level: Level, tag: &str, msg: F)
+    where
+        F: FnOnce() -> M,
+        M: fmt::Display,
+    {
+        if !cond || !self.enabled(&Metadata::builder().level(level).target(tag).build()) {
+            return;
+        }
+
+        let msg = msg();
+        self.emit(
+            &Record::builder()
+                .level(level)
+                .target(tag)
+                .args(format_args!("{msg}"))
+                .build(),
+            Some(tag),
+            None,
+        );
+    }
+}
+
+/// Logs the message built by `msg` through the global android logger, at the
+/// given `level` and under `tag`, if `cond` holds.
+///
+/// `msg` is only called if `cond` holds and `level` is enabled, so building
+/// the message costs nothing otherwise. Filters matching the message itself
+/// (see [`FilterBuilder::parse()`]) are applied once it is built.
+///
+/// ```
+/// # use log::Level;
+/// # let retries = 3;
+/// android_logger::log_if(retries > 2, Level::Warn, "my_app", || {
+///     format!("retried {retries} times")
+/// });
+/// ```
+pub fn log_if<F, M>(cond: bool, level: Level, tag: &str, msg: F)
+where
+    F: FnOnce() -> M,
+    M: fmt::Display,
+{
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .log_if(cond, level, tag, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+    use std::cell::Cell;
+
+    #[test]
+    fn message_is_built_only_if_logged() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+        let calls = Cell::new(0);
+        let msg = || {
+            calls.set(calls.get() + 1);
+            "built"
+        };
+
+        logger.log_if(false, Level::Warn, "cond", msg);
+        logger.log_if(true, Level::Debug, "cond", msg);
+        assert_eq!(calls.get(), 0);
+        assert!(take_emitted().is_empty());
+
+        logger.log_if(true, Level::Warn, "cond", msg);
+        assert_eq!(calls.get(), 1);
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].priority, Level::Warn);
+        assert_eq!(emitted[0].tag, "cond");
+        assert_eq!(emitted[0].msg, "built");
+    }
+}
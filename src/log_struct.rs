@@ -0,0 +1,152 @@
+//! Logging of the fields of a value as key-values.
+
+use super::*;
+use log::kv::{self, Key, Source, Value, VisitSource};
+
+/// A type whose fields can be logged as key-values, see [`log_struct()`].
+///
+/// ```
+/// use std::fmt;
+/// use android_logger::AsLogFields;
+///
+/// struct Download {
+///     url: String,
+///     bytes: u64,
+/// }
+///
+/// impl AsLogFields for Download {
+///     fn log_fields<'a>(&'a self, field: &mut dyn FnMut(&'a str, &'a dyn fmt::Display)) {
+///         field("url", &self.url);
+///         field("bytes", &self.bytes);
+///     }
+/// }
+/// ```
+pub trait AsLogFields {
+    /// Calls `field` with the name and value of each field to log, in order.
+    fn log_fields<'a>(&'a self, field: &mut dyn FnMut(&'a str, &'a dyn fmt::Display));
+}
+
+/// The fields of a value, as the key-values of a record.
+struct Fields<'a, T: ?Sized>(&'a T);
+
+impl<T: AsLogFields + ?Sized> Source for Fields<'_, T> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), kv::Error> {
+        let mut result = Ok(());
+        self.0.log_fields(&mut |name, value| {
+            if result.is_ok() {
+                result = visitor.visit_pair(Key::from_str(name), Value::from_dyn_display(value));
+            }
+        });
+        result
+    }
+}
+
+/// Name of `T` without its path and generic parameters, e.g. `Vec` for
+/// `alloc::vec::Vec<u8>`.
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+impl AndroidLogger {
+    /// Logs the fields of `value` as key-values, see [`log_struct()`].
+    pub fn log_struct<T: AsLogFields + ?Sized>(&self, level: Level, tag: &str, value: &T) {
+        if !self.enabled(&Metadata::builder().level(level).target(tag).build()) {
+            return;
+        }
+
+        let name = short_type_name::<T>();
+        self.emit(
+            &Record::builder()
+                .level(level)
+                .target(tag)
+                .args(format_args!("{name}"))
+                .key_values(&Fields(value))
+                .build(),
+            Some(tag),
+            None,
+        );
+    }
+}
+
+/// Logs the fields of `value` through the global android logger, as the
+/// key-values of a record whose message is the name of its type, at the
+/// given `level` and under `tag`.
+///
+/// The fields are written like any other key-values: as members of
+/// [`Config::format_json()`] objects, pairs of [`Config::format_logfmt()`]
+/// lines, or lines of [`Config::pretty_kv()`]. The plain format only shows
+/// the type name.
+///
+/// ```
+/// # use std::fmt;
+/// # use log::Level;
+/// # use android_logger::AsLogFields;
+/// # struct Download { url: String, bytes: u64 }
+/// # impl AsLogFields for Download {
+/// #     fn log_fields<'a>(&'a self, field: &mut dyn FnMut(&'a str, &'a dyn fmt::Display)) {
+/// #         field("url", &self.url);
+/// #         field("bytes", &self.bytes);
+/// #     }
+/// # }
+/// let download = Download { url: "https://example.com".to_owned(), bytes: 42 };
+/// // With `format_logfmt()`, logs:
+/// // level=info target=my_app msg="Download" url=https://example.com bytes=42
+/// android_logger::log_struct(Level::Info, "my_app", &download);
+/// ```
+pub fn log_struct<T: AsLogFields + ?Sized>(level: Level, tag: &str, value: &T) {
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .log_struct(level, tag, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    struct Download {
+        url: String,
+        bytes: u64,
+    }
+
+    impl AsLogFields for Download {
+        fn log_fields<'a>(&'a self, field: &mut dyn FnMut(&'a str, &'a dyn fmt::Display)) {
+            field("url", &self.url);
+            field("bytes", &self.bytes);
+        }
+    }
+
+    #[test]
+    fn fields_are_logged_as_key_values() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_json(),
+        );
+
+        logger.log_struct(
+            Level::Info,
+            "net",
+            &Download {
+                url: "https://example.com".to_owned(),
+                bytes: 42,
+            },
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            emitted[0].msg,
+            r#"{"level":"INFO","target":"net","msg":"Download","url":"https://example.com","bytes":"42"}"#
+        );
+    }
+
+    #[test]
+    fn short_type_names() {
+        assert_eq!(short_type_name::<Download>(), "Download");
+        assert_eq!(short_type_name::<Vec<u8>>(), "Vec");
+        assert_eq!(short_type_name::<str>(), "str");
+    }
+}
@@ -0,0 +1,242 @@
+//! logfmt output format.
+
+use super::*;
+use json::JsonEscaper;
+
+/// Writes `record` as a single logfmt line, e.g.:
+///
+/// ```text
+/// level=info target=my_app::net msg="connected to \"host\""
+/// ```
+///
+/// The base key-values of `config` are appended as further pairs, followed
+/// by the record's own key-values (with the `kv` feature), and by a `repeat`
+/// pair for the repeats of [`Config::defeat_chatty()`]. As in the JSON
+/// format, key-values named after one of the [`json::FIELDS`] are prefixed
+/// with `kv_`.
+pub(crate) fn write_record(
+    f: &mut dyn fmt::Write,
+    record: &Record,
+    config: &Config,
+    repeat: Option<u64>,
+) -> fmt::Result {
+    if let Some(version) = config.schema_version {
+        write!(f, "v={version} ")?;
+    }
+    f.write_str("level=")?;
+    f.write_str(level_name(record.level()))?;
+    f.write_str(" target=")?;
+    write_value(f, record.target())?;
+    // Messages almost always hold spaces, so they're always quoted, which
+    // allows to escape them while they're formatted.
+    f.write_str(" msg=\"")?;
+    write!(JsonEscaper(f), "{}", record.args())?;
+    f.write_char('"')?;
+    for (key, value) in config.base_kv() {
+        #[cfg(feature = "kv")]
+        if record.key_values().get(key.into()).is_some() && config.emits_kv(key) {
+            continue;
+        }
+        write_pair(f, key, value)?;
+    }
+    #[cfg(feature = "kv")]
+    {
+        let mut visitor = KvVisitor { f, config };
+        record
+            .key_values()
+            .visit(&mut visitor)
+            .map_err(|_| fmt::Error)?;
+    }
+    if let Some(repeat) = repeat {
+        write!(f, " repeat={repeat}")?;
+    }
+    Ok(())
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warn => "warn",
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    }
+}
+
+/// Writes the key-value ` key=value`.
+fn write_pair(f: &mut dyn fmt::Write, key: &str, value: &str) -> fmt::Result {
+    f.write_char(' ')?;
+    if json::FIELDS.contains(&key) {
+        f.write_str("kv_")?;
+    }
+    f.write_str(key)?;
+    f.write_char('=')?;
+    write_value(f, value)
+}
+
+/// Writes `value`, quoted and escaped if it's empty or holds spaces, `=`,
+/// `"`, `\` or control characters.
+pub(crate) fn write_value(f: &mut dyn fmt::Write, value: &str) -> fmt::Result {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control());
+    if needs_quotes {
+        f.write_char('"')?;
+        JsonEscaper(f).write_str(value)?;
+        f.write_char('"')
+    } else {
+        f.write_str(value)
+    }
+}
+
+#[cfg(feature = "kv")]
+struct KvVisitor<'a> {
+    f: &'a mut dyn fmt::Write,
+    config: &'a Config,
+}
+
+#[cfg(feature = "kv")]
+impl<'kvs> log::kv::VisitSource<'kvs> for KvVisitor<'_> {
+    fn visit_pair(
+        &mut self,
+        key: log::kv::Key<'kvs>,
+        value: log::kv::Value<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        if !self.config.emits_kv(key.as_str()) {
+            return Ok(());
+        }
+        match value.to_borrowed_str() {
+            Some(value) => write_pair(self.f, key.as_str(), value)?,
+            None => write_pair(self.f, key.as_str(), &value.to_string())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn value(s: &str) -> String {
+        let mut out = String::new();
+        write_value(&mut out, s).unwrap();
+        out
+    }
+
+    #[test]
+    fn quotes_values_when_needed() {
+        assert_eq!(value("plain"), "plain");
+        assert_eq!(value("1.2.3"), "1.2.3");
+        assert_eq!(value("héllo"), "héllo");
+        assert_eq!(value(""), r#""""#);
+        assert_eq!(value("two words"), r#""two words""#);
+        assert_eq!(value("a=b"), r#""a=b""#);
+        assert_eq!(value("say \"hi\""), r#""say \"hi\"""#);
+        assert_eq!(value("C:\\dir"), r#""C:\\dir""#);
+        assert_eq!(value("a\nb"), r#""a\nb""#);
+    }
+
+    #[test]
+    fn logfmt_formatted_record() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_base_kv(vec![
+                    ("app".to_owned(), "1.2.3".to_owned()),
+                    ("device".to_owned(), "Pixel 8".to_owned()),
+                ])
+                .format_logfmt(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Warn)
+                .target("app::net")
+                .args(format_args!("connection to \"host\" lost"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            emitted[0].msg,
+            r#"level=warn target=app::net msg="connection to \"host\" lost" app=1.2.3 device="Pixel 8""#
+        );
+    }
+
+    #[test]
+    fn logfmt_includes_schema_version() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_schema_version(1)
+                .format_logfmt(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(emitted[0].msg, r#"v=1 level=info target=app msg="msg""#);
+    }
+
+    #[cfg(feature = "kv")]
+    #[test]
+    fn logfmt_includes_key_values() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format_logfmt(),
+        );
+        let kvs = [
+            ("user", log::kv::Value::from("alice smith")),
+            ("attempt", log::kv::Value::from(3)),
+        ];
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("login"))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"level=info target=app msg="login" user="alice smith" attempt=3"#
+        );
+    }
+
+    #[test]
+    fn key_values_do_not_collide_with_fields() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_base_kv(vec![("target".to_owned(), "prod".to_owned())])
+                .format_logfmt(),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("app")
+                .args(format_args!("msg"))
+                .build(),
+        );
+
+        let emitted = take_emitted();
+        assert_eq!(
+            emitted[0].msg,
+            r#"level=info target=app msg="msg" kv_target=prod"#
+        );
+    }
+}
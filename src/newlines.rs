@@ -0,0 +1,96 @@
+//! Handling of newlines in messages.
+
+use super::*;
+
+/// How newlines in messages are logged, see [`Config::newline_handling()`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NewlineHandling {
+    /// Newlines are logged as is.
+    ///
+    /// Long multi-line messages are preferably split into several log
+    /// messages at newlines.
+    #[default]
+    Keep,
+
+    /// Each line is logged as a separate log message.
+    SplitIntoRecords,
+
+    /// Newlines are replaced with the two characters `\n`.
+    EscapeToLiteral,
+
+    /// Newlines are replaced with a space.
+    StripToSpace,
+}
+
+/// Writer applying a [`NewlineHandling`] to everything written to a
+/// [`PlatformLogWriter`].
+pub(crate) struct NewlineWriter<'w, 'a> {
+    pub(crate) writer: &'w mut PlatformLogWriter<'a>,
+    pub(crate) handling: NewlineHandling,
+}
+
+impl fmt::Write for NewlineWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // `None` splits into separate records.
+        let separator = match self.handling {
+            NewlineHandling::Keep => return self.writer.write_str(s),
+            NewlineHandling::SplitIntoRecords => None,
+            NewlineHandling::EscapeToLiteral => Some("\\n"),
+            NewlineHandling::StripToSpace => Some(" "),
+        };
+
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                match separator {
+                    Some(separator) => self.writer.write_str(separator)?,
+                    None => self.writer.flush(),
+                }
+            }
+            self.writer.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn log_with(handling: NewlineHandling) -> Vec<String> {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .newline_handling(handling),
+        );
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .args(format_args!("a\nb\nc"))
+                .build(),
+        );
+
+        take_emitted().into_iter().map(|e| e.msg).collect()
+    }
+
+    #[test]
+    fn keep_newlines() {
+        assert_eq!(log_with(NewlineHandling::Keep), ["a\nb\nc"]);
+    }
+
+    #[test]
+    fn split_lines_into_records() {
+        assert_eq!(log_with(NewlineHandling::SplitIntoRecords), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn escape_newlines() {
+        assert_eq!(log_with(NewlineHandling::EscapeToLiteral), ["a\\nb\\nc"]);
+    }
+
+    #[test]
+    fn strip_newlines() {
+        assert_eq!(log_with(NewlineHandling::StripToSpace), ["a b c"]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Logging of panics.
+
+use super::*;
+use std::panic;
+
+impl AndroidLogger {
+    /// Logs the panic described by `info`, regardless of the filter.
+    pub(crate) fn log_panic(&self, info: &dyn fmt::Display) {
+        let config = self.config();
+        let tag = config.internal_tag();
+        let buf_id = config.panic_buffer.or(config.buf_id);
+
+        let mut writer = config.writer(buf_id, Level::Error, &tag);
+        let _ = write!(writer, "{info}");
+        writer.flush();
+    }
+}
+
+/// Installs a panic hook logging panics through the global android logger,
+/// before calling the previously installed hook.
+///
+/// Panics are logged at the `Error` level, whatever the filter, under the
+/// configured tag (or [`INTERNAL_TAG`] if none is set), to the buffer set with
+/// [`Config::with_panic_buffer()`] (or the buffer of other logs if none is
+/// set). The message is the one the default hook writes to stderr, e.g.
+/// `panicked at src/main.rs:4:5:\nboom`, which is otherwise lost on Android.
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        ANDROID_LOGGER
+            .get_or_init(AndroidLogger::default)
+            .log_panic(info);
+        previous(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn panics_go_to_panic_buffer() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Off)
+                .with_log_buffer(LogId::System)
+                .with_panic_buffer(LogId::Crash),
+        );
+
+        logger.log_panic(&"panicked at src/lib.rs:1:1:\nboom");
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].buf_id, Some(LogId::Crash));
+        assert_eq!(emitted[0].priority, Level::Error);
+        assert_eq!(emitted[0].tag, INTERNAL_TAG.to_str().unwrap());
+        assert_eq!(emitted[0].msg, "panicked at src/lib.rs:1:1:\nboom");
+    }
+
+    #[test]
+    fn panics_default_to_log_buffer() {
+        let logger = AndroidLogger::new(Config::default().with_log_buffer(LogId::System));
+
+        logger.log_panic(&"boom");
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].buf_id, Some(LogId::System));
+    }
+
+    #[test]
+    fn panic_tag_is_truncated() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_tag("my-application-with-a-long-tag")
+                .with_display_tag_len(8),
+        );
+
+        logger.log_panic(&"boom");
+
+        assert_eq!(take_emitted()[0].tag, "my-app..");
+    }
+}
@@ -0,0 +1,161 @@
+//! Named formats, switched between at runtime.
+
+use super::*;
+
+/// Name of the preset of the format the [`Config`] was built with.
+const DEFAULT_PRESET: &str = "default";
+
+/// The format a logger currently uses, see [`set_active_format()`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum ActivePreset {
+    /// The format the [`Config`] was built with.
+    #[default]
+    Default,
+    /// [`Config::format_json()`].
+    Json,
+    /// [`Config::format_logfmt()`].
+    Logfmt,
+    /// The format registered at this index of [`Config::with_format_preset()`].
+    Registered(usize),
+}
+
+impl Config {
+    /// Returns the preset named `name`: the registered ones first, then the
+    /// built-in ones.
+    fn preset(&self, name: &str) -> Option<ActivePreset> {
+        if let Some(index) = self
+            .format_presets
+            .iter()
+            .position(|(preset, _)| preset == name)
+        {
+            return Some(ActivePreset::Registered(index));
+        }
+        match name {
+            DEFAULT_PRESET => Some(ActivePreset::Default),
+            "json" => Some(ActivePreset::Json),
+            "logfmt" => Some(ActivePreset::Logfmt),
+            _ => None,
+        }
+    }
+
+    /// Returns the format function and output format of `preset`.
+    pub(crate) fn preset_format(&self, preset: ActivePreset) -> (Option<&FormatFn>, OutputFormat) {
+        match preset {
+            ActivePreset::Default => (self.custom_format.as_ref(), self.output_format),
+            ActivePreset::Json => (None, OutputFormat::Json),
+            ActivePreset::Logfmt => (None, OutputFormat::Logfmt),
+            ActivePreset::Registered(index) => {
+                (Some(&self.format_presets[index].1), OutputFormat::Plain)
+            }
+        }
+    }
+}
+
+impl AndroidLogger {
+    /// Switches this logger to the format preset `name`, see
+    /// [`set_active_format()`].
+    pub fn set_active_format(&self, name: &str) -> bool {
+        let Some(preset) = self.config().preset(name) else {
+            return false;
+        };
+        *self
+            .active_preset
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = preset;
+        true
+    }
+
+    /// Returns the format function and output format records are currently
+    /// formatted with.
+    pub(crate) fn active_format(&self) -> (Option<&FormatFn>, OutputFormat) {
+        let preset = *self
+            .active_preset
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.config().preset_format(preset)
+    }
+}
+
+/// Switches the global android logger to the format preset `name`, for the
+/// records logged afterwards.
+///
+/// This allows e.g. a debug menu to change the format of the logs without
+/// restarting the app. `name` is either one registered with
+/// [`Config::with_format_preset()`], or one of the built-in presets:
+///
+/// - `default`: the format the [`Config`] was built with (e.g. with
+///   [`Config::format()`]), used until another preset is activated;
+/// - `json`: the format of [`Config::format_json()`];
+/// - `logfmt`: the format of [`Config::format_logfmt()`].
+///
+/// Returns whether the preset exists, leaving the format unchanged if it
+/// doesn't, or if the global android logger isn't initialized yet.
+///
+/// ```
+/// use android_logger::Config;
+///
+/// android_logger::init_once(
+///     Config::default().with_format_preset("verbose", |f, record| {
+///         write!(f, "{}:{} {}", record.target(), record.line().unwrap_or(0), record.args())
+///     }),
+/// );
+///
+/// assert!(android_logger::set_active_format("verbose"));
+/// assert!(android_logger::set_active_format("json"));
+/// assert!(!android_logger::set_active_format("unknown"));
+/// ```
+pub fn set_active_format(name: &str) -> bool {
+    ANDROID_LOGGER
+        .get()
+        .is_some_and(|logger| logger.set_active_format(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn switching_presets_changes_format() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_format_preset("compact", |f, record| write!(f, "{}", record.args()))
+                .with_format_preset("verbose", |f, record| {
+                    write!(
+                        f,
+                        "{} {}: {}",
+                        record.level(),
+                        record.target(),
+                        record.args()
+                    )
+                }),
+        );
+        let log = || logger.log(&test::record(Level::Info, "app", None, format_args!("msg")));
+
+        log();
+        assert!(logger.set_active_format("verbose"));
+        log();
+        assert!(logger.set_active_format("compact"));
+        log();
+        assert!(logger.set_active_format("logfmt"));
+        log();
+        assert!(!logger.set_active_format("unknown"));
+        log();
+        assert!(logger.set_active_format("default"));
+        log();
+
+        let msgs: Vec<_> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(
+            msgs,
+            [
+                "msg",
+                "INFO app: msg",
+                "msg",
+                r#"level=info target=app msg="msg""#,
+                r#"level=info target=app msg="msg""#,
+                "msg",
+            ]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Conversions between [`Level`]s and Android priorities.
+
+use super::*;
+
+pub use log_ffi::LogPriority;
+
+/// Maps a [`Level`] to the Android priority it is logged with.
+///
+/// [`Config::trace_as_debug()`] aside, this is how the logger maps levels.
+pub fn level_to_priority(level: Level) -> LogPriority {
+    match level {
+        Level::Warn => LogPriority::WARN,
+        Level::Info => LogPriority::INFO,
+        Level::Debug => LogPriority::DEBUG,
+        Level::Error => LogPriority::ERROR,
+        Level::Trace => LogPriority::VERBOSE,
+    }
+}
+
+/// Maps an Android priority back to its [`Level`], the inverse of
+/// [`level_to_priority()`], e.g. to express a message received with an
+/// Android priority as a `log` record.
+///
+/// `FATAL` has no equivalent level (the closest being [`Level::Error`],
+/// which loses the severity), and neither have `SILENT` (which isn't a
+/// priority messages are logged with, but one filtering out all of them),
+/// `UNKNOWN` and `DEFAULT`: `None` is returned for them.
+pub fn priority_to_level(priority: LogPriority) -> Option<Level> {
+    match priority {
+        LogPriority::VERBOSE => Some(Level::Trace),
+        LogPriority::DEBUG => Some(Level::Debug),
+        LogPriority::INFO => Some(Level::Info),
+        LogPriority::WARN => Some(Level::Warn),
+        LogPriority::ERROR => Some(Level::Error),
+        LogPriority::UNKNOWN | LogPriority::DEFAULT | LogPriority::FATAL | LogPriority::SILENT => {
+            None
+        }
+    }
+}
+
+/// Maps an Android priority to the closest [`Level`].
+pub(crate) fn closest_level(priority: LogPriority) -> Level {
+    priority_to_level(priority).unwrap_or(Level::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_round_trip() {
+        for level in [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ] {
+            assert_eq!(priority_to_level(level_to_priority(level)), Some(level));
+        }
+    }
+
+    #[test]
+    fn priorities_without_level() {
+        for priority in [
+            LogPriority::UNKNOWN,
+            LogPriority::DEFAULT,
+            LogPriority::FATAL,
+            LogPriority::SILENT,
+        ] {
+            assert_eq!(priority_to_level(priority), None);
+        }
+        assert_eq!(closest_level(LogPriority::FATAL), Level::Error);
+    }
+}
@@ -0,0 +1,63 @@
+//! Marking of records with the build profile.
+
+/// Name of the profile, according to whether `debug_assertions` are enabled.
+pub(crate) fn name(debug: bool) -> &'static str {
+    if debug {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+/// Adds the build profile of the calling crate, `debug` or `release`, to
+/// every record logged with the given [`Config`](crate::Config).
+///
+/// `cfg!(debug_assertions)` is evaluated in the crate it's written in, so
+/// [`Config::with_profile_marker()`](crate::Config::with_profile_marker)
+/// can only detect the profile `android_logger` was built with. This macro
+/// expands in the calling crate instead, and passes its profile to
+/// [`Config::with_build_profile()`](crate::Config::with_build_profile).
+///
+/// ```
+/// use android_logger::Config;
+///
+/// let config = android_logger::with_profile!(Config::default());
+/// // logs e.g. "[debug] started"
+/// # android_logger::init_once(config);
+/// ```
+#[macro_export]
+macro_rules! with_profile {
+    ($config:expr) => {
+        $crate::Config::with_build_profile($config, ::std::cfg!(debug_assertions))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::log_msgs;
+    use crate::*;
+
+    #[test]
+    fn marker_reflects_profile() {
+        let msgs = log_msgs(
+            crate::with_profile!(Config::default().with_max_level(LevelFilter::Info)),
+            &[test::record(
+                Level::Info,
+                "app",
+                None,
+                format_args!("started"),
+            )],
+        );
+
+        let expected = if cfg!(debug_assertions) {
+            "[debug] started"
+        } else {
+            "[release] started"
+        };
+        assert_eq!(msgs, [expected]);
+        assert_eq!(
+            Config::default().with_build_profile(false).profile,
+            Some("release")
+        );
+    }
+}
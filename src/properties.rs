@@ -0,0 +1,198 @@
+//! Runtime reconfiguration via Android system properties.
+
+use super::*;
+
+impl AndroidLogger {
+    /// Reads the property `name` with `read` and applies the level it holds
+    /// as the new filter of this logger.
+    ///
+    /// Returns the applied level, or `None` if the property is unset or does
+    /// not hold a valid level.
+    fn apply_level_property<R>(&self, name: &str, read: R) -> Option<LevelFilter>
+    where
+        R: FnOnce(&str) -> Option<String>,
+    {
+        let level = parse_level(&read(name)?)?;
+        self.set_filter(FilterBuilder::new().filter_level(level).build());
+        Some(level)
+    }
+
+    /// Reads the `log.tag.<tag>` property with `read`, falling back to the
+    /// persistent `persist.log.tag.<tag>` one like liblog does, for the tag
+    /// set with [`Config::with_tag()`], and applies the level it holds as the
+    /// new filter of this logger.
+    ///
+    /// Returns the applied level, or `None` if no tag is set, or neither
+    /// property is set to a valid level.
+    fn apply_tag_properties<R>(&self, read: R) -> Option<LevelFilter>
+    where
+        R: Fn(&str) -> Option<String>,
+    {
+        let tag = self.config().tag.as_ref()?.to_str().ok()?;
+        ["log.tag.", "persist.log.tag."]
+            .into_iter()
+            .find_map(|prefix| self.apply_level_property(&format!("{prefix}{tag}"), &read))
+    }
+}
+
+/// Applies the tag properties of the global android logger, if enabled with
+/// [`Config::honor_tag_properties()`], on initialization.
+///
+/// Returns the applied level.
+#[cfg(target_os = "android")]
+pub(crate) fn apply_tag_properties_on_init(logger: &AndroidLogger) -> Option<LevelFilter> {
+    if !logger.config().honor_tag_properties {
+        return None;
+    }
+    logger.apply_tag_properties(read_system_property)
+}
+
+/// Parses a property value as a level.
+///
+/// Both the names of [`LevelFilter`] and the Android priority names (and
+/// their first letters, as used by `log.tag.*` properties) are accepted,
+/// case-insensitively.
+fn parse_level(value: &str) -> Option<LevelFilter> {
+    let value = value.trim();
+    let level = match value.to_ascii_lowercase().as_str() {
+        "v" | "verbose" => LevelFilter::Trace,
+        "d" => LevelFilter::Debug,
+        "i" => LevelFilter::Info,
+        "w" => LevelFilter::Warn,
+        "e" => LevelFilter::Error,
+        "s" | "silent" | "suppress" => LevelFilter::Off,
+        _ => value.parse().ok()?,
+    };
+    Some(level)
+}
+
+/// Reads the Android system property `name`.
+#[cfg(target_os = "android")]
+fn read_system_property(name: &str) -> Option<String> {
+    /// Maximum length of a property value, including the terminating `\0`.
+    const PROP_VALUE_MAX: usize = 92;
+
+    extern "C" {
+        fn __system_property_get(
+            name: *const log_ffi::c_char,
+            value: *mut log_ffi::c_char,
+        ) -> log_ffi::c_int;
+    }
+
+    let name = CString::new(name).ok()?;
+    let mut value = [0u8; PROP_VALUE_MAX];
+    let len = unsafe { __system_property_get(name.as_ptr(), value.as_mut_ptr().cast()) };
+    if len <= 0 {
+        return None;
+    }
+    let value = CStr::from_bytes_until_nul(&value).ok()?;
+    Some(value.to_string_lossy().into_owned())
+}
+
+/// Applies the level held by the Android system property `name` (e.g.
+/// `debug.myapp.loglevel`) to the global android logger.
+///
+/// This allows changing the verbosity of a running process with
+/// `adb shell setprop debug.myapp.loglevel debug`, without restarting it.
+/// Both the [`LevelFilter`] names (`off`, `error`, .., `trace`) and the
+/// Android priority names (`V`, `D`, `I`, `W`, `E`, `S`) are accepted.
+///
+/// On success the property level replaces the configured filter (see
+/// [`AndroidLogger::set_filter()`]), [`log::set_max_level()`] is updated
+/// accordingly, and the applied level is returned. If the property is unset
+/// or holds an invalid value, nothing changes and `None` is returned.
+///
+/// Reading a property is a lookup in shared memory, so it is cheap, but not
+/// free. Call this on a signal, or periodically at a coarse cadence (every
+/// few seconds), rather than on every log call.
+#[cfg(target_os = "android")]
+pub fn poll_system_property(name: &str) -> Option<LevelFilter> {
+    let level = ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .apply_level_property(name, read_system_property)?;
+    log::set_max_level(level);
+    Some(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_level_names() {
+        assert_eq!(parse_level("debug"), Some(LevelFilter::Debug));
+        assert_eq!(parse_level("TRACE"), Some(LevelFilter::Trace));
+        assert_eq!(parse_level(" off\n"), Some(LevelFilter::Off));
+        assert_eq!(parse_level("V"), Some(LevelFilter::Trace));
+        assert_eq!(parse_level("w"), Some(LevelFilter::Warn));
+        assert_eq!(parse_level("S"), Some(LevelFilter::Off));
+        assert_eq!(parse_level("loud"), None);
+        assert_eq!(parse_level(""), None);
+    }
+
+    #[test]
+    fn level_property_replaces_filter() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+        let debug = log::MetadataBuilder::new().level(Level::Debug).build();
+        assert!(!logger.enabled(&debug));
+
+        let applied = logger.apply_level_property("debug.app.loglevel", |name| {
+            assert_eq!(name, "debug.app.loglevel");
+            Some("debug".to_owned())
+        });
+
+        assert_eq!(applied, Some(LevelFilter::Debug));
+        assert!(logger.enabled(&debug));
+        assert!(logger.filter_matches(&Record::builder().level(Level::Debug).build()));
+        assert!(!logger.filter_matches(&Record::builder().level(Level::Trace).build()));
+    }
+
+    #[test]
+    fn tag_properties() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("app"),
+        );
+        let debug = log::MetadataBuilder::new().level(Level::Debug).build();
+
+        let applied = logger.apply_tag_properties(|name| match name {
+            "log.tag.app" => None,
+            "persist.log.tag.app" => Some("D".to_owned()),
+            _ => panic!("unexpected property {name}"),
+        });
+        assert_eq!(applied, Some(LevelFilter::Debug));
+        assert!(logger.enabled(&debug));
+
+        // The non-persistent property takes precedence.
+        let applied = logger.apply_tag_properties(|name| match name {
+            "log.tag.app" => Some("W".to_owned()),
+            _ => Some("V".to_owned()),
+        });
+        assert_eq!(applied, Some(LevelFilter::Warn));
+    }
+
+    #[test]
+    fn tag_properties_need_a_tag() {
+        let logger = AndroidLogger::new(Config::default());
+
+        assert_eq!(
+            logger.apply_tag_properties(|_| Some("debug".to_owned())),
+            None
+        );
+    }
+
+    #[test]
+    fn missing_or_invalid_property_keeps_filter() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+        let debug = log::MetadataBuilder::new().level(Level::Debug).build();
+
+        assert_eq!(logger.apply_level_property("unset", |_| None), None);
+        assert_eq!(
+            logger.apply_level_property("invalid", |_| Some("loud".to_owned())),
+            None
+        );
+
+        assert!(!logger.enabled(&debug));
+    }
+}
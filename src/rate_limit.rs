@@ -0,0 +1,115 @@
+//! Rate limiting of the records of each tag.
+
+use super::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Number of distinct tags tracked at once.
+const CAPACITY: usize = 256;
+
+/// The start of the current interval of each tag, and the number of its
+/// records emitted since.
+type Intervals = HashMap<Vec<u8>, (Instant, u32)>;
+
+/// Budget of records of each tag, see [`Config::with_rate_limit_per_tag()`].
+#[derive(Clone)]
+pub(crate) struct TagRateLimit {
+    max: u32,
+    interval: Duration,
+    tags: State<Mutex<Intervals>>,
+}
+
+impl TagRateLimit {
+    pub(crate) fn new(max: u32, interval: Duration) -> TagRateLimit {
+        TagRateLimit {
+            max,
+            interval,
+            tags: State::default(),
+        }
+    }
+
+    /// Counts a record of `tag`, emitted at `now`.
+    ///
+    /// Returns whether it's within the budget of `tag`, and must be emitted.
+    pub(crate) fn check(&self, tag: &[u8], now: Instant) -> bool {
+        let mut tags = self.tags.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((start, count)) = tags.get_mut(tag) {
+            if now.saturating_duration_since(*start) >= self.interval {
+                *start = now;
+                *count = 0;
+            }
+            *count += 1;
+            return *count <= self.max;
+        }
+
+        if tags.len() == CAPACITY {
+            self.evict(&mut tags, now);
+        }
+        tags.insert(tag.to_owned(), (now, 1));
+        self.max > 0
+    }
+
+    /// Forgets the tags whose interval is over, or the one whose interval
+    /// started first if there are none.
+    fn evict(&self, tags: &mut Intervals, now: Instant) {
+        tags.retain(|_, &mut (start, _)| now.saturating_duration_since(start) < self.interval);
+        if tags.len() == CAPACITY {
+            if let Some(oldest) = tags
+                .iter()
+                .min_by_key(|(_, &(start, _))| start)
+                .map(|(tag, _)| tag.clone())
+            {
+                tags.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::log_emitted;
+
+    #[test]
+    fn each_tag_has_its_own_budget() {
+        let record =
+            |module_path| test::record(Level::Info, "", Some(module_path), format_args!("msg"));
+        let mut records = vec![record("chatty"); 5];
+        records.extend([record("quiet"), record("quiet")]);
+
+        let emitted = log_emitted(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_rate_limit_per_tag(2, Duration::from_secs(60)),
+            &records,
+        );
+
+        let tags: Vec<_> = emitted.into_iter().map(|e| e.tag).collect();
+        assert_eq!(tags, ["chatty", "chatty", "quiet", "quiet"]);
+    }
+
+    #[test]
+    fn budget_is_renewed_every_interval() {
+        let limit = TagRateLimit::new(1, Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert!(limit.check(b"tag", start));
+        assert!(!limit.check(b"tag", start + Duration::from_millis(500)));
+        assert!(limit.check(b"tag", start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn idle_tags_are_evicted() {
+        let limit = TagRateLimit::new(1, Duration::from_secs(1));
+        let start = Instant::now();
+
+        for i in 0..CAPACITY {
+            assert!(limit.check(format!("tag{i}").as_bytes(), start));
+        }
+        assert!(!limit.check(b"tag0", start));
+
+        let later = start + Duration::from_secs(1);
+        assert!(limit.check(b"new", later));
+        assert_eq!(limit.tags.lock().unwrap().len(), 1);
+    }
+}
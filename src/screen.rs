@@ -0,0 +1,73 @@
+//! Name of the screen currently shown by the app.
+
+use super::*;
+
+/// Maximum length of the screen name in a message, in bytes. Longer names
+/// are truncated, so they don't eat into the length of messages.
+const MAX_SCREEN_LEN: usize = 64;
+
+/// Set by [`set_current_screen()`].
+static CURRENT_SCREEN: RwLock<Option<String>> = RwLock::new(None);
+
+/// Sets the name of the screen (e.g. activity or fragment) currently shown
+/// by the app, or clears it with `None`.
+///
+/// With [`Config::with_screen_name()`], messages are then prefixed with
+/// `[screen=<name>] `, giving them their UI context without passing it to
+/// every log call. The logger has no access to the Android context, so the
+/// app must call this as it navigates, e.g. from `onResume()`. Unlike
+/// correlation ids (see [`set_correlation_id()`]), the screen is shared by
+/// all threads. Names longer than 64 bytes are truncated.
+pub fn set_current_screen(name: Option<&str>) {
+    *CURRENT_SCREEN
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = name.map(str::to_owned);
+}
+
+/// Writes the `[screen=<name>] ` prefix, if a screen is set.
+pub(crate) fn write_prefix(f: &mut dyn fmt::Write) -> fmt::Result {
+    match &*CURRENT_SCREEN
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+    {
+        Some(name) => {
+            let mut len = name.len().min(MAX_SCREEN_LEN);
+            while !name.is_char_boundary(len) {
+                len -= 1;
+            }
+            write!(f, "[screen={}] ", &name[..len])
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    #[test]
+    fn messages_are_prefixed_with_screen() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_screen_name(true),
+        );
+        let log = || {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("tapped"))
+                    .build(),
+            )
+        };
+
+        set_current_screen(Some("SettingsActivity"));
+        log();
+        set_current_screen(None);
+        log();
+
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["[screen=SettingsActivity] tapped", "tapped"]);
+    }
+}
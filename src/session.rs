@@ -0,0 +1,72 @@
+//! Random identifiers of logger sessions.
+
+use super::*;
+use std::sync::atomic::AtomicU64;
+use std::time::UNIX_EPOCH;
+
+/// Number of session ids generated so far, so that ids generated at the same
+/// time differ.
+static GENERATED: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a random session id of 8 hex digits, see
+/// [`Config::with_session_id()`].
+///
+/// This is a xorshift of the current time (and of the number of ids already
+/// generated), so it is unique enough to tell sessions apart, but neither
+/// unpredictable nor collision-free.
+pub(crate) fn generate() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let generated = GENERATED.fetch_add(1, Ordering::Relaxed);
+
+    let mut x = (nanos ^ generated.wrapping_mul(0x9e37_79b9_7f4a_7c15)) | 1;
+    for _ in 0..4 {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+    }
+    format!("{:08x}", x as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn session_ids(logger: &AndroidLogger) -> Vec<String> {
+        for _ in 0..2 {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("msg"))
+                    .build(),
+            );
+        }
+        take_emitted()
+            .into_iter()
+            .map(|e| {
+                let id = e.msg.strip_prefix("[session=").unwrap();
+                assert_eq!(&id[8..], "] msg");
+                id[..8].to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn session_id_is_stable_per_logger() {
+        let config = || {
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_session_id(true)
+        };
+        let first = session_ids(&AndroidLogger::new(config()));
+        let second = session_ids(&AndroidLogger::new(config()));
+
+        assert_eq!(first[0], first[1]);
+        assert_eq!(second[0], second[1]);
+        assert_ne!(first[0], second[0]);
+        assert!(first[0].bytes().all(|b| b.is_ascii_hexdigit()));
+    }
+}
@@ -0,0 +1,70 @@
+//! Teardown of the logger.
+
+use super::*;
+
+impl AndroidLogger {
+    /// Flushes this logger, stops its background threads and makes it inert,
+    /// see [`shutdown()`].
+    pub fn shutdown(&self) {
+        self.shut_down.store(true, Ordering::Relaxed);
+        self.flush();
+        // Joins the heartbeat thread.
+        drop(
+            self.heartbeat
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take(),
+        );
+    }
+}
+
+/// Flushes the global android logger, stops its background threads (e.g.
+/// the one of [`Config::with_heartbeat()`]) and makes it inert, so that
+/// subsequent records are dropped.
+///
+/// This is meant for libraries which initialized the logger and are about to
+/// be unloaded, as no thread of theirs may outlive them. The logger can't be
+/// uninstalled from the `log` facade, which keeps pointing at it, so any
+/// later `log` call is a no-op rather than logged. Calling [`init_once()`]
+/// again doesn't bring it back, and neither does this allow installing
+/// another logger.
+///
+/// Does nothing if the global android logger was never used.
+pub fn shutdown() {
+    if let Some(logger) = ANDROID_LOGGER.get() {
+        logger.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{take_all_emitted, take_emitted};
+    use std::thread;
+
+    #[test]
+    fn shutdown_stops_heartbeat_and_logging() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("shutdown")
+                .with_heartbeat(Duration::from_millis(5), Level::Info, "alive"),
+        );
+
+        logger.shutdown();
+        assert!(logger.heartbeat.lock().unwrap().is_none());
+
+        take_all_emitted("shutdown");
+        thread::sleep(Duration::from_millis(20));
+        assert!(take_all_emitted("shutdown").is_empty());
+
+        logger.log(
+            &Record::builder()
+                .level(Level::Error)
+                .args(format_args!("after shutdown"))
+                .build(),
+        );
+        assert!(take_emitted().is_empty());
+        assert!(!logger.enabled(&Metadata::builder().level(Level::Error).build()));
+    }
+}
@@ -0,0 +1,170 @@
+//! Copy of the log messages to a Unix domain socket.
+
+use super::*;
+use std::io::{self, Write as _};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+/// Minimum time between two attempts to connect to the socket.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The socket of [`Config::with_socket()`].
+#[derive(Clone)]
+pub(crate) struct SocketTee {
+    path: PathBuf,
+    state: State<Mutex<SocketState>>,
+}
+
+#[derive(Default)]
+struct SocketState {
+    stream: Option<UnixStream>,
+    last_attempt: Option<Instant>,
+}
+
+impl SocketTee {
+    pub(crate) fn new(path: PathBuf) -> SocketTee {
+        SocketTee {
+            path,
+            state: State::default(),
+        }
+    }
+
+    /// Writes the line `<priority>/<tag>: <msg>`, e.g. `I/my_app: started`,
+    /// connecting first if needed.
+    ///
+    /// The line is dropped if it can't be written without blocking.
+    pub(crate) fn write(&self, priority: i32, tag: &CStr, msg: &CStr) {
+        let mut line = Vec::with_capacity(tag.count_bytes() + msg.count_bytes() + 5);
+        line.extend_from_slice(&[priority_char(priority), b'/']);
+        line.extend_from_slice(tag.to_bytes());
+        line.extend_from_slice(b": ");
+        line.extend_from_slice(msg.to_bytes());
+        line.push(b'\n');
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.stream.is_none() {
+            let now = Instant::now();
+            if state
+                .last_attempt
+                .is_some_and(|last| now.saturating_duration_since(last) < RECONNECT_DELAY)
+            {
+                return;
+            }
+            state.last_attempt = Some(now);
+            state.stream = connect(&self.path).ok();
+        }
+
+        if let Some(stream) = &mut state.stream {
+            match stream.write(&line) {
+                Ok(written) if written == line.len() => {}
+                // The reader is too slow: drop the line, but keep the
+                // connection.
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                // A partial line would be glued to the next one, start over
+                // with a new connection instead.
+                Ok(_) | Err(_) => state.stream = None,
+            }
+        }
+    }
+}
+
+fn connect(path: &Path) -> io::Result<UnixStream> {
+    let stream = UnixStream::connect(path)?;
+    stream.set_nonblocking(true)?;
+    Ok(stream)
+}
+
+/// Returns the letter logcat shows for the Android priority `priority`.
+fn priority_char(priority: i32) -> u8 {
+    match priority {
+        2 => b'V',
+        3 => b'D',
+        4 => b'I',
+        5 => b'W',
+        6 => b'E',
+        7 => b'F',
+        _ => b'?',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixListener;
+
+    #[test]
+    fn lines_arrive_on_socket() {
+        let path = std::env::temp_dir().join(format!(
+            "android_logger-socket-test-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_socket(&path),
+        );
+        for (level, msg) in [(Level::Info, "started"), (Level::Error, "failed")] {
+            logger.log(&test::record(level, "", Some("app"), format_args!("{msg}")));
+        }
+
+        let (stream, _) = listener.accept().unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let lines: Vec<String> = BufReader::new(stream)
+            .lines()
+            .take(2)
+            .map(Result::unwrap)
+            .collect();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(lines, ["I/app: started", "E/app: failed"]);
+    }
+
+    #[test]
+    fn missing_socket_is_ignored() {
+        let tee = SocketTee::new(std::env::temp_dir().join("android_logger-no-such.sock"));
+
+        tee.write(4, c"app", c"dropped");
+        assert!(tee.state.lock().unwrap().stream.is_none());
+    }
+
+    #[test]
+    fn captured_messages_are_not_copied() {
+        let path = std::env::temp_dir().join(format!(
+            "android_logger-socket-capture-test-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        listener.set_nonblocking(true).unwrap();
+
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_socket(&path),
+        );
+        let captured = test_capture(|| {
+            logger.log(&test::record(
+                Level::Info,
+                "app",
+                None,
+                format_args!("captured"),
+            ));
+        });
+
+        let accepted = listener.accept();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(captured.len(), 1);
+        assert_eq!(
+            accepted.unwrap_err().kind(),
+            io::ErrorKind::WouldBlock,
+            "the socket was connected to"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Preview of how messages are split into log messages.
+
+use super::*;
+
+impl AndroidLogger {
+    /// Returns the log messages `msg` would be split into, without logging
+    /// anything.
+    ///
+    /// Only the splitting applies: `msg` is neither filtered nor formatted
+    /// (e.g. no prefixes are added), but the limits of the configuration are
+    /// honored, i.e. [`Config::with_total_payload_len()`] (with `tag`),
+    /// [`Config::max_chunks_per_record()`] and [`Config::no_split()`]. This
+    /// shows exactly where long messages are split: preferably before their
+    /// last newline fitting into a log message, which then starts the next
+    /// one.
+    ///
+    /// ```
+    /// # use log::Level;
+    /// # use android_logger::{AndroidLogger, Config};
+    /// let logger = AndroidLogger::new(Config::default());
+    /// let msg = format!("{}\n{}", "a".repeat(3000), "b".repeat(3000));
+    ///
+    /// let chunks = logger.split_preview(Level::Info, "my_app", &msg);
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[0], "a".repeat(3000));
+    /// assert!(chunks[1].starts_with("\nb"));
+    /// ```
+    ///
+    /// With the `deny-logging` feature, nothing is returned.
+    pub fn split_preview(&self, level: Level, tag: &str, msg: &str) -> Vec<String> {
+        let config = self.config();
+
+        let mut tag_bytes: [MaybeUninit<u8>; LOGGING_TAG_MAX_LEN + 1] = uninit_array();
+        self.fill_tag_bytes(&mut tag_bytes, tag.as_bytes());
+        let tag: &CStr = unsafe { CStr::from_ptr(tag_bytes.as_ptr().cast()) };
+
+        let mut writer = PlatformLogWriter::new(None, level, tag);
+        writer.max_chunks = config.max_chunks.unwrap_or(usize::MAX);
+        writer.max_len = config.message_len_for_tag(tag);
+        if config.no_split {
+            writer.max_chunks = 1;
+            writer.truncation_marker = &[];
+        }
+
+        test_capture(|| {
+            let _ = writer.write_str(msg);
+            writer.flush();
+        })
+        .into_iter()
+        .map(|record| record.msg)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lens(chunks: &[String]) -> Vec<usize> {
+        chunks.iter().map(String::len).collect()
+    }
+
+    #[test]
+    fn split_at_max_len() {
+        let logger = AndroidLogger::default();
+        let preview = |msg: &str| logger.split_preview(Level::Info, "app", msg);
+
+        assert!(preview("").is_empty());
+        assert_eq!(lens(&preview(&"a".repeat(LOGGING_MSG_MAX_LEN))), [4000]);
+        assert_eq!(
+            lens(&preview(&"a".repeat(LOGGING_MSG_MAX_LEN + 1))),
+            [4000, 1]
+        );
+    }
+
+    #[test]
+    fn split_after_last_newline() {
+        let logger = AndroidLogger::default();
+        let msg = format!(
+            "{}\n{}\n{}",
+            "a".repeat(10),
+            "b".repeat(3000),
+            "c".repeat(3000)
+        );
+
+        let chunks = logger.split_preview(Level::Info, "app", &msg);
+
+        assert_eq!(lens(&chunks), [3011, 3001]);
+        assert!(chunks[0].ends_with('b'));
+        assert_eq!(chunks[1], format!("\n{}", "c".repeat(3000)));
+    }
+
+    #[test]
+    fn split_honors_config_limits() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_total_payload_len(53)
+                .max_chunks_per_record(2),
+        );
+
+        let chunks = logger.split_preview(Level::Info, "app", &"a".repeat(200));
+
+        assert_eq!(lens(&chunks), [47, 47]);
+        assert!(chunks[1].ends_with("…[truncated]"));
+    }
+
+    #[test]
+    fn preview_is_not_logged() {
+        let logger = AndroidLogger::default();
+
+        logger.split_preview(Level::Info, "app", "hello");
+
+        assert!(crate::tests::take_emitted().is_empty());
+    }
+}
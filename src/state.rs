@@ -0,0 +1,28 @@
+//! State of the features tracking the records a logger logs.
+
+use std::ops::Deref;
+
+/// State of a logger (e.g. the recent messages of
+/// [`Config::dedup_window()`]), kept along with its settings in [`Config`].
+///
+/// Clones start afresh, from the default value, so that each logger created
+/// from a cloned [`Config`] has its own state.
+///
+/// [`Config`]: crate::Config
+/// [`Config::dedup_window()`]: crate::Config::dedup_window
+#[derive(Default)]
+pub(crate) struct State<T>(T);
+
+impl<T: Default> Clone for State<T> {
+    fn clone(&self) -> State<T> {
+        State::default()
+    }
+}
+
+impl<T> Deref for State<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
@@ -0,0 +1,153 @@
+//! Accounting of the records dropped by the suppression features.
+
+use super::*;
+use std::sync::atomic::AtomicU64;
+
+/// Counts of the records suppressed since the last summary, see
+/// [`Config::summarize_suppressions()`].
+#[derive(Clone, Default)]
+pub(crate) struct Suppressions {
+    /// Dropped by [`Config::dedup_window()`].
+    pub(crate) deduped: State<AtomicU64>,
+    /// Dropped by [`Config::log_first_n()`].
+    pub(crate) over_first_n: State<AtomicU64>,
+    /// Dropped by [`Config::with_rate_limit_per_tag()`].
+    pub(crate) rate_limited: State<AtomicU64>,
+}
+
+impl Suppressions {
+    /// Writes the summary of the records suppressed since the last one, e.g.
+    /// `suppressed: 7 deduped, 3 over first-n limit`, and resets the counts.
+    ///
+    /// Returns whether anything was suppressed.
+    fn write_summary(&self, f: &mut dyn fmt::Write) -> Result<bool, fmt::Error> {
+        let counts = [
+            (self.deduped.swap(0, Ordering::Relaxed), "deduped"),
+            (
+                self.over_first_n.swap(0, Ordering::Relaxed),
+                "over first-n limit",
+            ),
+            (self.rate_limited.swap(0, Ordering::Relaxed), "rate limited"),
+        ];
+
+        let mut written = false;
+        for (count, reason) in counts.into_iter().filter(|&(count, _)| count > 0) {
+            f.write_str(if written { ", " } else { "suppressed: " })?;
+            write!(f, "{count} {reason}")?;
+            written = true;
+        }
+        Ok(written)
+    }
+}
+
+impl AndroidLogger {
+    /// Logs the summary of the records suppressed since the last one, if
+    /// enabled with [`Config::summarize_suppressions()`].
+    pub(crate) fn log_suppression_summary(&self) {
+        let config = self.config();
+        let Some(suppressions) = &config.suppressions else {
+            return;
+        };
+
+        let mut summary = String::new();
+        if let Ok(true) = suppressions.write_summary(&mut summary) {
+            let tag = config.internal_tag();
+            let mut writer = config.writer(config.buf_id, config.level(Level::Info), &tag);
+            let _ = writer.write_str(&summary);
+            writer.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+    use std::sync::Arc;
+
+    #[test]
+    fn flush_logs_summary_of_suppressed_records() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("summary")
+                .dedup_window(Duration::from_secs(60), 16)
+                .log_first_n(1)
+                .summarize_suppressions(true),
+        );
+        for _ in 0..3 {
+            logger.log(&test::record(
+                Level::Info,
+                "",
+                None,
+                format_args!("repeated"),
+            ));
+        }
+        take_emitted();
+
+        logger.flush();
+        let emitted: Vec<String> = take_emitted().into_iter().map(|e| e.msg).collect();
+        assert_eq!(emitted, ["suppressed: 2 deduped"]);
+
+        // Counts are reset by the summary.
+        logger.flush();
+        assert!(take_emitted().is_empty());
+    }
+
+    #[test]
+    fn summary_lists_each_reason() {
+        let suppressions = Suppressions::default();
+        suppressions.deduped.store(7, Ordering::Relaxed);
+        suppressions.over_first_n.store(3, Ordering::Relaxed);
+        suppressions.rate_limited.store(2, Ordering::Relaxed);
+
+        let mut summary = String::new();
+        assert_eq!(suppressions.write_summary(&mut summary), Ok(true));
+        assert_eq!(
+            summary,
+            "suppressed: 7 deduped, 3 over first-n limit, 2 rate limited"
+        );
+    }
+
+    #[test]
+    fn summary_is_written_like_records() {
+        /// Collects the tags and messages written.
+        #[derive(Default)]
+        struct Collector(Mutex<Vec<(String, String)>>);
+
+        impl LogBackend for Collector {
+            fn write(&self, _: Option<LogId>, _: Level, tag: &CStr, msg: &CStr) {
+                self.0.lock().unwrap().push((
+                    tag.to_str().unwrap().to_owned(),
+                    msg.to_str().unwrap().to_owned(),
+                ));
+            }
+        }
+
+        let collector = Arc::new(Collector::default());
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_tag("summary-with-a-long-tag")
+                .with_display_tag_len(10)
+                .with_backend(collector.clone())
+                .log_first_n(1)
+                .summarize_suppressions(true),
+        );
+        for _ in 0..2 {
+            logger.log(&test::record(
+                Level::Info,
+                "",
+                None,
+                format_args!("repeated"),
+            ));
+        }
+        logger.flush();
+
+        assert!(take_emitted().is_empty());
+        let written = collector.0.lock().unwrap();
+        let tags: Vec<_> = written.iter().map(|(tag, _)| tag.as_str()).collect();
+        assert_eq!(tags, ["summary-..", "summary-..", "summary-.."]);
+        assert_eq!(written[2].1, "suppressed: 1 over first-n limit");
+    }
+}
@@ -0,0 +1,74 @@
+//! Counting of the records of each tag.
+
+use super::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Number of distinct tags counted, later ones aren't.
+const CAPACITY: usize = 1024;
+
+/// The number of records emitted under each tag, see
+/// [`Config::with_per_tag_counter()`].
+#[derive(Clone, Default)]
+pub(crate) struct TagCounters {
+    counters: State<Mutex<HashMap<Vec<u8>, u64>>>,
+}
+
+impl TagCounters {
+    /// Returns the number of records emitted under `tag` so far, and counts
+    /// one more, or `None` if `tag` isn't counted.
+    pub(crate) fn next(&self, tag: &[u8]) -> Option<u64> {
+        let mut counters = self.counters.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(counter) = counters.get_mut(tag) {
+            *counter += 1;
+            return Some(*counter);
+        }
+        if counters.len() == CAPACITY {
+            return None;
+        }
+        counters.insert(tag.to_owned(), 0);
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::log_emitted;
+
+    #[test]
+    fn each_tag_is_counted_independently() {
+        let record =
+            |module_path| test::record(Level::Info, "", Some(module_path), format_args!("msg"));
+        let emitted = log_emitted(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .with_per_tag_counter(true),
+            &["net", "net", "ui", "net", "ui"].map(record),
+        );
+
+        let emitted: Vec<_> = emitted.into_iter().map(|e| (e.tag, e.msg)).collect();
+        assert_eq!(
+            emitted,
+            [
+                ("net", "#0 msg"),
+                ("net", "#1 msg"),
+                ("ui", "#0 msg"),
+                ("net", "#2 msg"),
+                ("ui", "#1 msg"),
+            ]
+            .map(|(tag, msg)| (tag.to_owned(), msg.to_owned()))
+        );
+    }
+
+    #[test]
+    fn tags_past_capacity_are_not_counted() {
+        let counters = TagCounters::default();
+        for i in 0..CAPACITY {
+            assert_eq!(counters.next(format!("tag{i}").as_bytes()), Some(0));
+        }
+
+        assert_eq!(counters.next(b"tag0"), Some(1));
+        assert_eq!(counters.next(b"new"), None);
+    }
+}
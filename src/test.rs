@@ -0,0 +1,71 @@
+//! Helpers for the tests of code using the android logger.
+
+use super::*;
+
+/// Builds a [`Record`] of `level`, `target` and `module_path`, whose message
+/// is `args`, e.g. to pass to [`AndroidLogger::log()`] or to a format function
+/// (see [`Config::format()`]).
+///
+/// Other metadata, like the file and line, is left empty.
+///
+/// ```
+/// use log::{Level, LevelFilter, Log};
+/// use android_logger::{test, AndroidLogger, Config};
+///
+/// let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Info));
+/// let captured = android_logger::test_capture(|| {
+///     logger.log(&test::record(Level::Warn, "app", Some("app::net"), format_args!("lost")));
+/// });
+///
+/// assert_eq!(captured[0].tag, "app::net");
+/// assert_eq!(captured[0].msg, "lost");
+/// ```
+pub fn record<'a>(
+    level: Level,
+    target: &'a str,
+    module_path: Option<&'a str>,
+    args: fmt::Arguments<'a>,
+) -> Record<'a> {
+    Record::builder()
+        .level(level)
+        .target(target)
+        .module_path(module_path)
+        .args(args)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_goes_through_format() {
+        let format = |f: &mut dyn fmt::Write, record: &Record| {
+            write!(
+                f,
+                "{} {} {}: {}",
+                record.level(),
+                record.target(),
+                record.module_path().unwrap_or_default(),
+                record.args()
+            )
+        };
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_max_level(LevelFilter::Info)
+                .format(format),
+        );
+
+        let captured = test_capture(|| {
+            logger.log(&record(
+                Level::Info,
+                "app",
+                Some("app::net"),
+                format_args!("{} bytes", 42),
+            ))
+        });
+
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].msg, "INFO app app::net: 42 bytes");
+    }
+}
@@ -0,0 +1,123 @@
+//! Timing of scopes.
+
+use super::*;
+use std::thread;
+
+impl AndroidLogger {
+    /// Starts timing a scope, see [`timed()`].
+    pub fn timed<'a>(&'a self, level: Level, tag: &'a str, label: &'a str) -> Timed<'a> {
+        Timed {
+            logger: self,
+            level,
+            tag,
+            label,
+            start: self.config().clock().now(),
+        }
+    }
+}
+
+/// Starts timing a scope, logging how long it took once the returned guard
+/// is dropped.
+///
+/// The duration is logged through the global android logger as
+/// `"{label} took {elapsed:?}"`, at the given `level` and under `tag`. If the
+/// guard is dropped while unwinding from a panic, ` (panicked)` is appended.
+///
+/// ```
+/// # use log::Level;
+/// {
+///     let _timed = android_logger::timed(Level::Debug, "my_app", "loading assets");
+///
+///     // ..
+/// } // logs e.g. "loading assets took 12.3ms"
+/// ```
+pub fn timed<'a>(level: Level, tag: &'a str, label: &'a str) -> Timed<'a> {
+    ANDROID_LOGGER
+        .get_or_init(AndroidLogger::default)
+        .timed(level, tag, label)
+}
+
+/// Guard logging the time elapsed since its creation when dropped.
+///
+/// Created by [`timed()`].
+#[must_use = "the duration is logged when the guard is dropped"]
+pub struct Timed<'a> {
+    logger: &'a AndroidLogger,
+    level: Level,
+    tag: &'a str,
+    label: &'a str,
+    start: Instant,
+}
+
+impl Drop for Timed<'_> {
+    fn drop(&mut self) {
+        let now = self.logger.config().clock().now();
+        let elapsed = now.saturating_duration_since(self.start);
+        let panicked = if thread::panicking() {
+            " (panicked)"
+        } else {
+            ""
+        };
+        self.logger.emit(
+            &Record::builder()
+                .level(self.level)
+                .target(self.tag)
+                .args(format_args!(
+                    "{} took {:?}{}",
+                    self.label, elapsed, panicked
+                ))
+                .build(),
+            Some(self.tag),
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::time::Duration;
+
+    /// Parses the duration logged by [`Timed`], in milliseconds.
+    fn logged_millis(msg: &str) -> f64 {
+        let elapsed = msg.split(" took ").nth(1).unwrap();
+        let millis = elapsed.split("ms").next().unwrap();
+        millis.parse().unwrap()
+    }
+
+    #[test]
+    fn logs_duration_on_drop() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Debug));
+
+        {
+            let _timed = logger.timed(Level::Debug, "timer", "sleeping");
+            thread::sleep(Duration::from_millis(20));
+            assert!(take_emitted().is_empty());
+        }
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].tag, "timer");
+        assert_eq!(emitted[0].priority, Level::Debug);
+        assert!(emitted[0].msg.starts_with("sleeping took "));
+        assert!(logged_millis(&emitted[0].msg) >= 20.0);
+    }
+
+    #[test]
+    fn notes_panic() {
+        let logger = AndroidLogger::new(Config::default().with_max_level(LevelFilter::Debug));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _timed = logger.timed(Level::Info, "timer", "failing");
+            panic!("failure");
+        }));
+
+        assert!(result.is_err());
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert!(emitted[0].msg.starts_with("failing took "));
+        assert!(emitted[0].msg.ends_with(" (panicked)"));
+    }
+}
@@ -0,0 +1,84 @@
+//! Machine-readable description of the output, for log-viewing tools.
+
+use super::*;
+
+impl AndroidLogger {
+    /// Logs the tooling header, if enabled with
+    /// [`Config::emit_tooling_header()`].
+    pub(crate) fn log_tooling_header(&self) {
+        let config = self.config();
+        if !config.tooling_header {
+            return;
+        }
+
+        let tag = config.internal_tag();
+        let mut writer = config.writer(config.buf_id, config.level(Level::Info), &tag);
+        let _ = write_header(&mut writer, config);
+        writer.flush();
+    }
+}
+
+/// Writes the header describing the output configured in `config`, see
+/// [`Config::emit_tooling_header()`].
+fn write_header(f: &mut dyn fmt::Write, config: &Config) -> fmt::Result {
+    let format = match (&config.custom_format, config.output_format) {
+        (Some(_), _) => "custom",
+        (_, OutputFormat::Plain) => "plain",
+        (_, OutputFormat::Json) => "json",
+        (_, OutputFormat::Logfmt) => "logfmt",
+    };
+    write!(f, "#android_logger format={format}")?;
+    if let Some(version) = config.schema_version {
+        write!(f, " schema={version}")?;
+    }
+    if let Some(tag) = &config.tag {
+        f.write_str(" tag=")?;
+        logfmt::write_value(f, &tag.to_string_lossy())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::take_emitted;
+
+    fn header(config: Config) -> String {
+        let mut out = String::new();
+        write_header(&mut out, &config).unwrap();
+        out
+    }
+
+    #[test]
+    fn header_describes_output() {
+        assert_eq!(header(Config::default()), "#android_logger format=plain");
+        assert_eq!(
+            header(
+                Config::default()
+                    .with_tag("my app")
+                    .with_schema_version(2)
+                    .format_json()
+            ),
+            r#"#android_logger format=json schema=2 tag="my app""#
+        );
+        assert_eq!(
+            header(Config::default().format(|f, record| write!(f, "{}", record.args()))),
+            "#android_logger format=custom"
+        );
+    }
+
+    #[test]
+    fn header_tag_is_truncated() {
+        let logger = AndroidLogger::new(
+            Config::default()
+                .with_tag("my-application-with-a-long-tag")
+                .emit_tooling_header(true),
+        );
+
+        logger.log_tooling_header();
+
+        let emitted = take_emitted();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].tag, "my-application-with-a..");
+    }
+}
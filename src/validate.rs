@@ -0,0 +1,112 @@
+//! Validation of configurations.
+
+use super::*;
+
+/// Error returned by [`Config::validate()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The tag is longer than the maximum length supported by liblog, or the
+    /// limit set with [`Config::with_display_tag_len()`], so it would be
+    /// truncated.
+    TagTooLong {
+        /// Length of the tag, in bytes.
+        len: usize,
+        /// Maximum length of tags, in bytes.
+        max_len: usize,
+    },
+
+    /// A filter directive could not be parsed, so it would be ignored.
+    InvalidDirective(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::TagTooLong { len, max_len } => write!(
+                f,
+                "tag is {len} bytes long, but at most {max_len} bytes are logged"
+            ),
+            ConfigError::InvalidDirective(directive) => {
+                write!(f, "invalid filter directive `{directive}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Checks this configuration for mistakes, without installing it.
+    ///
+    /// Mistakes in a configuration don't prevent logging, but make it behave
+    /// differently than intended: tags too long are truncated, and invalid
+    /// filter directives (see [`Config::parse_filters()`]) are ignored. This
+    /// allows to catch them early, e.g. in a test or before
+    /// [`init_once()`], and returns the first one found.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(tag) = &self.tag {
+            let len = tag.as_bytes().len();
+            let max_len = self.display_tag_len.unwrap_or(LOGGING_TAG_MAX_LEN);
+            if len > max_len {
+                return Err(ConfigError::TagTooLong { len, max_len });
+            }
+        }
+
+        if let Some(directive) = self.directives.iter().flat_map(Directives::invalid).next() {
+            return Err(ConfigError::InvalidDirective(directive.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_config() {
+        let config = Config::default()
+            .with_tag("my_app")
+            .with_display_tag_len(10)
+            .parse_filters("info,my_crate=debug");
+
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn tag_too_long() {
+        let config = Config::default().with_tag("a".repeat(30));
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::TagTooLong {
+                len: 30,
+                max_len: LOGGING_TAG_MAX_LEN
+            })
+        );
+
+        let config = Config::default()
+            .with_tag("my_application")
+            .with_display_tag_len(10);
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::TagTooLong {
+                len: 14,
+                max_len: 10
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_directive() {
+        let config = Config::default().parse_filters("info,my_crate=loud");
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(
+            err,
+            ConfigError::InvalidDirective("my_crate=loud".to_owned())
+        );
+        assert_eq!(err.to_string(), "invalid filter directive `my_crate=loud`");
+    }
+}
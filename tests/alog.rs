@@ -0,0 +1,39 @@
+extern crate android_logger;
+extern crate log;
+
+use std::cell::Cell;
+use std::fmt;
+
+use android_logger::{alog, Config};
+use log::{Level, LevelFilter};
+
+/// Counts how many times it's formatted.
+struct Counted<'a>(&'a Cell<u32>);
+
+impl fmt::Display for Counted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.set(self.0.get() + 1);
+        f.write_str("counted")
+    }
+}
+
+#[test]
+fn alog_uses_given_tag() {
+    android_logger::init_once(
+        Config::default()
+            .with_max_level(LevelFilter::Info)
+            .with_tag("configured"),
+    );
+    let formatted = Cell::new(0);
+
+    let captured = android_logger::test_capture(|| {
+        alog!(Level::Warn, "call_site", "{} of {}", 2, Counted(&formatted));
+        alog!(Level::Debug, "call_site", "{}", Counted(&formatted));
+    });
+
+    assert_eq!(captured.len(), 1);
+    assert_eq!(captured[0].level, Level::Warn);
+    assert_eq!(captured[0].tag, "call_site");
+    assert_eq!(captured[0].msg, "2 of counted");
+    assert_eq!(formatted.get(), 1);
+}
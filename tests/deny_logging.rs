@@ -0,0 +1,88 @@
+#![cfg(feature = "deny-logging")]
+
+extern crate android_logger;
+extern crate log;
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "logging is disabled"))]
+fn deny_logging() {
+    log::set_max_level(log::LevelFilter::Trace);
+    android_logger::log(
+        &log::Record::builder()
+            .level(log::Level::Error)
+            .args(format_args!("leaked"))
+            .build(),
+    );
+}
+
+/// The copy to a socket is denied too: in release builds, it's never even
+/// connected to.
+#[test]
+#[cfg(unix)]
+#[cfg_attr(debug_assertions, should_panic(expected = "logging is disabled"))]
+fn deny_logging_to_socket() {
+    use log::Log;
+    use std::os::unix::net::UnixListener;
+
+    let path = std::env::temp_dir().join(format!(
+        "android_logger-deny-logging-{}.sock",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    listener.set_nonblocking(true).unwrap();
+
+    let logger = android_logger::AndroidLogger::new(
+        android_logger::Config::default()
+            .with_max_level(log::LevelFilter::Trace)
+            .with_socket(&path),
+    );
+    logger.log(&android_logger::test::record(
+        log::Level::Error,
+        "app",
+        None,
+        format_args!("leaked"),
+    ));
+
+    let accepted = listener.accept();
+    let _ = std::fs::remove_file(&path);
+    assert!(accepted.is_err(), "the socket was connected to");
+}
+
+/// Records aren't mirrored either.
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "logging is disabled"))]
+fn deny_logging_to_mirror() {
+    use log::Log;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static MIRRORED: AtomicBool = AtomicBool::new(false);
+
+    struct Mirror;
+
+    impl Log for Mirror {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, _: &log::Record) {
+            MIRRORED.store(true, Ordering::Relaxed);
+        }
+
+        fn flush(&self) {}
+    }
+
+    let logger = android_logger::AndroidLogger::new(
+        android_logger::Config::default()
+            .with_max_level(log::LevelFilter::Trace)
+            .mirror_to(Mirror),
+    );
+    logger.log(&android_logger::test::record(
+        log::Level::Error,
+        "app",
+        None,
+        format_args!("leaked"),
+    ));
+
+    assert!(!MIRRORED.load(Ordering::Relaxed), "the record was mirrored");
+}
@@ -0,0 +1,42 @@
+extern crate android_logger;
+extern crate log;
+
+use android_logger::Config;
+use log::{Level, Record};
+
+fn log(level: Level, msg: &str) {
+    android_logger::log(
+        &Record::builder()
+            .level(level)
+            .module_path(Some("app"))
+            .args(format_args!("{msg}"))
+            .build(),
+    );
+}
+
+#[test]
+fn early_records_are_replayed_on_init() {
+    android_logger::enable_early_buffering(2);
+
+    let captured = android_logger::test_capture(|| {
+        log(Level::Trace, "dropped on overflow");
+        log(Level::Debug, "early debug");
+        log(Level::Trace, "early trace");
+    });
+    assert!(captured.is_empty());
+
+    let captured = android_logger::test_capture(|| {
+        android_logger::init_once(Config::default().with_max_level(log::LevelFilter::Trace));
+        log(Level::Info, "after init");
+    });
+
+    let captured: Vec<_> = captured.iter().map(|r| (r.level, &*r.msg)).collect();
+    assert_eq!(
+        captured,
+        [
+            (Level::Debug, "early debug"),
+            (Level::Trace, "early trace"),
+            (Level::Info, "after init"),
+        ]
+    );
+}
@@ -0,0 +1,38 @@
+extern crate android_logger;
+extern crate log;
+
+use android_logger::{test, AndroidLogger, Config, FilterBuilder};
+use log::{Level, LevelFilter, Log};
+
+#[test]
+fn drop_by_max_level_is_explained_once() {
+    log::set_max_level(LevelFilter::Info);
+    let logger = AndroidLogger::new(
+        Config::default()
+            .with_filter(FilterBuilder::new().parse("debug").build())
+            .explain_drops(true),
+    );
+
+    let captured = android_logger::test_capture(|| {
+        for _ in 0..2 {
+            logger.log(&test::record(
+                Level::Debug,
+                "app",
+                None,
+                format_args!("details"),
+            ));
+        }
+    });
+
+    if cfg!(debug_assertions) {
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].tag, "android_logger");
+        assert_eq!(
+            captured[0].msg,
+            "record at DEBUG dropped by max_level=INFO though filter permits it; \
+             call log::set_max_level higher"
+        );
+    } else {
+        assert!(captured.is_empty());
+    }
+}
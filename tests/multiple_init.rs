@@ -8,9 +8,13 @@ fn multiple_init() {
     );
 
     // Second initialization should be silently ignored
-    android_logger::init_once(
-        android_logger::Config::default().with_max_level(log::LevelFilter::Error),
-    );
+    let captured = android_logger::test_capture(|| {
+        android_logger::init_once(
+            android_logger::Config::default().with_max_level(log::LevelFilter::Error),
+        );
+    });
+
+    assert!(captured.is_empty(), "{captured:?}");
 
     assert_eq!(log::max_level(), log::LevelFilter::Trace);
 }
@@ -0,0 +1,27 @@
+extern crate android_logger;
+extern crate log;
+
+use android_logger::{Config, LogId};
+
+#[test]
+fn panic_hook() {
+    android_logger::init_once(
+        Config::default()
+            .with_max_level(log::LevelFilter::Info)
+            .with_tag("app")
+            .with_panic_buffer(LogId::Crash),
+    );
+    android_logger::install_panic_hook();
+
+    let captured = android_logger::test_capture(|| {
+        log::info!("before");
+        let _ = std::panic::catch_unwind(|| panic!("boom"));
+    });
+
+    assert_eq!(captured.len(), 2);
+    assert_eq!(captured[0].buf_id, None);
+    assert_eq!(captured[1].buf_id, Some(LogId::Crash));
+    assert_eq!(captured[1].level, log::Level::Error);
+    assert!(captured[1].msg.starts_with("panicked at "));
+    assert!(captured[1].msg.ends_with("boom"));
+}
@@ -0,0 +1,26 @@
+extern crate android_logger;
+extern crate log;
+
+#[test]
+fn tooling_header_is_logged_first() {
+    let captured = android_logger::test_capture(|| {
+        android_logger::init_once(
+            android_logger::Config::default()
+                .with_max_level(log::LevelFilter::Info)
+                .with_tag("app")
+                .with_schema_version(1)
+                .format_logfmt()
+                .emit_tooling_header(true),
+        );
+        log::info!(target: "app", "started");
+    });
+
+    let msgs: Vec<_> = captured.iter().map(|record| record.msg.as_str()).collect();
+    assert_eq!(
+        msgs,
+        [
+            "#android_logger format=logfmt schema=1 tag=app",
+            r#"v=1 level=info target=app msg="started""#,
+        ]
+    );
+}
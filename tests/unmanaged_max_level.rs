@@ -0,0 +1,15 @@
+extern crate android_logger;
+extern crate log;
+
+#[test]
+fn unmanaged_max_level() {
+    log::set_max_level(log::LevelFilter::Warn);
+
+    android_logger::init_once(
+        android_logger::Config::default()
+            .with_max_level(log::LevelFilter::Trace)
+            .manage_max_level(false),
+    );
+
+    assert_eq!(log::max_level(), log::LevelFilter::Warn);
+}